@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+use chrono::Local;
+use crate::domain::entities::{Alert, AlertKind, AlertRules, AlertSeverity, UsageStats};
+
+/// Costs for the periods that are not part of `UsageStats` (week, month).
+#[derive(Debug, Clone, Default)]
+pub struct PeriodCosts {
+  pub weekly_cost: Option<f32>,
+  pub monthly_cost: Option<f32>,
+}
+
+struct Candidate {
+  key: String,
+  alert: Option<Alert>,
+}
+
+/// Edge-triggered alert evaluation: an alert fires once when its condition
+/// becomes true and re-arms only after the condition clears again.
+#[derive(Default)]
+pub struct AlertEngine {
+  active: HashSet<String>,
+}
+
+impl AlertEngine {
+  pub fn new() -> Self {
+    Self { active: HashSet::new() }
+  }
+
+  pub fn evaluate(&mut self, rules: &AlertRules, stats: &UsageStats, period_costs: &PeriodCosts) -> Vec<Alert> {
+    let mut fired = Vec::new();
+    let mut still_active = HashSet::new();
+    for candidate in candidates(rules, stats, period_costs) {
+      if !self.active.contains(&candidate.key) {
+        if let Some(alert) = candidate.alert {
+          fired.push(alert);
+        }
+      }
+      still_active.insert(candidate.key);
+    }
+    self.active = still_active;
+    fired
+  }
+
+  /// Evaluation while alerts are snoozed: conditions that clear are re-armed
+  /// as usual, but new ones are not marked active, so they fire once the
+  /// snooze ends if they still hold.
+  pub fn evaluate_muted(&mut self, rules: &AlertRules, stats: &UsageStats, period_costs: &PeriodCosts) {
+    let current: HashSet<String> = candidates(rules, stats, period_costs).into_iter().map(|candidate| candidate.key).collect();
+    self.active.retain(|key| current.contains(key));
+  }
+}
+
+fn candidates(rules: &AlertRules, stats: &UsageStats, period_costs: &PeriodCosts) -> Vec<Candidate> {
+  let mut candidates = Vec::new();
+
+  let budgets = [
+    ("day", "cost", rules.daily_budget_usd, Some(stats.cost)),
+    ("block", "session_cost", rules.block_budget_usd, Some(stats.session_cost)),
+    ("week", "weekly_cost", rules.weekly_budget_usd, period_costs.weekly_cost),
+    ("month", "monthly_cost", rules.monthly_budget_usd, period_costs.monthly_cost),
+  ];
+  for (period, field, limit, spent) in budgets {
+    if let (Some(limit), Some(spent)) = (limit, spent) {
+      budget_candidates(&mut candidates, rules, stats, period, field, limit, spent);
+    }
+  }
+
+  if let (Some(threshold), Some(burn_rate)) = (rules.runaway_burn_rate, stats.burn_rate) {
+    if stats.active_session && burn_rate >= threshold {
+      candidates.push(Candidate {
+        key: "runaway_session".to_string(),
+        alert: Some(build_alert(
+          AlertKind::RunawaySession,
+          AlertSeverity::Warning,
+          "Runaway session detected".to_string(),
+          format!("Burn rate is {:.0} tokens/min (threshold {:.0})", burn_rate, threshold),
+          "burn_rate",
+          burn_rate,
+          threshold,
+          stats,
+        )),
+      });
+    }
+  }
+
+  candidates
+}
+
+fn budget_candidates(
+  candidates: &mut Vec<Candidate>,
+  rules: &AlertRules,
+  stats: &UsageStats,
+  period: &str,
+  field: &str,
+  limit: f32,
+  spent: f32,
+) {
+  if limit <= 0.0 {
+    return;
+  }
+  let percent = spent / limit * 100.0;
+  let warning_key = format!("budget_warning:{}", period);
+  let exceeded_key = format!("budget_exceeded:{}", period);
+
+  if percent >= 100.0 {
+    candidates.push(Candidate {
+      key: exceeded_key,
+      alert: Some(build_alert(
+        AlertKind::BudgetExceeded,
+        AlertSeverity::Critical,
+        format!("{} budget exceeded", period_label(period)),
+        format!("Spent ${:.2} of ${:.2} ({:.0}%)", spent, limit, percent),
+        field,
+        spent,
+        limit,
+        stats,
+      )),
+    });
+    // Keep the warning armed silently so it does not fire after the breach.
+    candidates.push(Candidate { key: warning_key, alert: None });
+  } else if percent >= rules.budget_warning_percent {
+    candidates.push(Candidate {
+      key: warning_key,
+      alert: Some(build_alert(
+        AlertKind::BudgetWarning,
+        AlertSeverity::Warning,
+        format!("{} budget at {:.0}%", period_label(period), percent),
+        format!("Spent ${:.2} of ${:.2}", spent, limit),
+        field,
+        spent,
+        limit * rules.budget_warning_percent / 100.0,
+        stats,
+      )),
+    });
+  }
+}
+
+fn period_label(period: &str) -> &'static str {
+  match period {
+    "week" => "Weekly",
+    "month" => "Monthly",
+    "block" => "Block",
+    _ => "Daily",
+  }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn build_alert(
+  kind: AlertKind,
+  severity: AlertSeverity,
+  title: String,
+  message: String,
+  trigger_field: &str,
+  trigger_value: f32,
+  threshold: f32,
+  stats: &UsageStats,
+) -> Alert {
+  Alert {
+    kind,
+    severity,
+    title,
+    message,
+    trigger_field: trigger_field.to_string(),
+    trigger_value,
+    threshold,
+    stats: stats.clone(),
+    timestamp: Local::now().to_rfc3339(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rules() -> AlertRules {
+    AlertRules { daily_budget_usd: Some(10.0), ..AlertRules::default() }
+  }
+
+  fn stats(cost: f32) -> UsageStats {
+    UsageStats { cost, ..UsageStats::default() }
+  }
+
+  fn kinds(alerts: &[Alert]) -> Vec<AlertKind> {
+    alerts.iter().map(|alert| alert.kind).collect()
+  }
+
+  #[test]
+  fn fires_once_and_re_arms_after_clearing() {
+    let mut engine = AlertEngine::new();
+    let costs = PeriodCosts::default();
+
+    assert_eq!(kinds(&engine.evaluate(&rules(), &stats(8.5), &costs)), vec![AlertKind::BudgetWarning]);
+    assert!(engine.evaluate(&rules(), &stats(9.0), &costs).is_empty());
+
+    assert!(engine.evaluate(&rules(), &stats(2.0), &costs).is_empty());
+    assert_eq!(kinds(&engine.evaluate(&rules(), &stats(8.5), &costs)), vec![AlertKind::BudgetWarning]);
+  }
+
+  #[test]
+  fn exceeding_the_budget_does_not_fire_the_warning_afterwards() {
+    let mut engine = AlertEngine::new();
+    let costs = PeriodCosts::default();
+
+    assert_eq!(kinds(&engine.evaluate(&rules(), &stats(12.0), &costs)), vec![AlertKind::BudgetExceeded]);
+    assert!(engine.evaluate(&rules(), &stats(13.0), &costs).is_empty());
+  }
+
+  #[test]
+  fn muted_evaluation_leaves_new_conditions_armed() {
+    let mut engine = AlertEngine::new();
+    let costs = PeriodCosts::default();
+
+    engine.evaluate_muted(&rules(), &stats(8.5), &costs);
+    assert_eq!(kinds(&engine.evaluate(&rules(), &stats(8.5), &costs)), vec![AlertKind::BudgetWarning]);
+
+    engine.evaluate_muted(&rules(), &stats(2.0), &costs);
+    assert_eq!(kinds(&engine.evaluate(&rules(), &stats(8.5), &costs)), vec![AlertKind::BudgetWarning]);
+  }
+}
@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+use crate::domain::entities::{Alert, AlertKind, AlertRules, AlertSeverity, PlanLimits, UsageStats};
+use super::alerts::build_alert;
+
+struct TrackedBlock {
+  start_time: String,
+  end_time: DateTime<Utc>,
+  ending_notified: bool,
+  limit_hit: bool,
+}
+
+/// Follows the active 5-hour block across polls and reports when a block
+/// starts, is about to end, and when a block that hit its limit resets.
+#[derive(Default)]
+pub struct BlockTracker {
+  current: Option<TrackedBlock>,
+  initialized: bool,
+}
+
+impl BlockTracker {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn update(&mut self, rules: &AlertRules, stats: &UsageStats, limits: &PlanLimits, now: DateTime<Utc>) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    let active = active_block(stats);
+
+    let same_block = matches!(
+      (&self.current, &active),
+      (Some(current), Some((start_time, _))) if &current.start_time == start_time
+    );
+    if !same_block {
+      if let Some(previous) = self.current.take() {
+        if previous.limit_hit && rules.block_reset_alerts {
+          alerts.push(build_alert(
+            AlertKind::BlockReset,
+            AlertSeverity::Info,
+            "Usage block reset".to_string(),
+            "The block that hit its limit has ended. You can resume heavy work.".to_string(),
+            "block_end_time",
+            0.0,
+            0.0,
+            stats,
+          ));
+        }
+      }
+      if let Some((start_time, end_time)) = active {
+        // Blocks already running at startup are adopted silently.
+        if self.initialized && rules.block_start_alerts {
+          alerts.push(build_alert(
+            AlertKind::BlockStarted,
+            AlertSeverity::Info,
+            "New usage block started".to_string(),
+            format!("A new 5-hour block started, ending at {}", end_time.with_timezone(&chrono::Local).format("%H:%M")),
+            "current_tokens",
+            stats.current_tokens as f32,
+            0.0,
+            stats,
+          ));
+        }
+        self.current = Some(TrackedBlock { start_time, end_time, ending_notified: false, limit_hit: false });
+      }
+    }
+
+    if let Some(current) = self.current.as_mut() {
+      current.limit_hit |= block_limit_reached(rules, stats, limits);
+
+      let minutes_left = (current.end_time - now).num_minutes();
+      if let Some(threshold) = rules.block_ending_minutes {
+        if !current.ending_notified && minutes_left >= 0 && minutes_left <= threshold as i64 {
+          current.ending_notified = true;
+          alerts.push(build_alert(
+            AlertKind::BlockEndingSoon,
+            AlertSeverity::Warning,
+            format!("Usage block ends in {} min", minutes_left),
+            format!("The active block resets at {}", current.end_time.with_timezone(&chrono::Local).format("%H:%M")),
+            "block_minutes_remaining",
+            minutes_left as f32,
+            threshold as f32,
+            stats,
+          ));
+        }
+      }
+    }
+
+    self.initialized = true;
+    alerts
+  }
+}
+
+fn active_block(stats: &UsageStats) -> Option<(String, DateTime<Utc>)> {
+  if !stats.active_session {
+    return None;
+  }
+  let start_time = stats.block_start_time.clone()?;
+  let end_time = DateTime::parse_from_rfc3339(stats.block_end_time.as_deref()?).ok()?;
+  Some((start_time, end_time.with_timezone(&Utc)))
+}
+
+/// A detected limit is only the heaviest block so far; passing it is not a hit.
+fn block_limit_reached(rules: &AlertRules, stats: &UsageStats, limits: &PlanLimits) -> bool {
+  rules.block_budget_usd.is_some_and(|budget| budget > 0.0 && stats.session_cost >= budget)
+    || (!limits.block_detected && stats.percent_of_block_limit.is_some_and(|percent| percent >= 100.0))
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::TimeZone;
+  use super::*;
+
+  const LIMITS: PlanLimits = PlanLimits { block_tokens: Some(1000), weekly_tokens: None, block_detected: false };
+  const DETECTED: PlanLimits = PlanLimits { block_tokens: Some(1000), weekly_tokens: None, block_detected: true };
+
+  fn rules() -> AlertRules {
+    AlertRules { block_start_alerts: true, block_ending_minutes: Some(15), block_reset_alerts: true, ..AlertRules::default() }
+  }
+
+  fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, 19, hour, minute, 0).unwrap()
+  }
+
+  /// A block running from `start_hour` for five hours, at `percent` of its limit.
+  fn active(start_hour: u32, percent: f32) -> UsageStats {
+    UsageStats {
+      active_session: true,
+      block_start_time: Some(at(start_hour, 0).to_rfc3339()),
+      block_end_time: Some(at(start_hour + 5, 0).to_rfc3339()),
+      percent_of_block_limit: Some(percent),
+      ..UsageStats::default()
+    }
+  }
+
+  fn kinds(alerts: &[Alert]) -> Vec<AlertKind> {
+    alerts.iter().map(|alert| alert.kind).collect()
+  }
+
+  #[test]
+  fn adopts_a_block_already_running_at_startup() {
+    let mut tracker = BlockTracker::new();
+    assert!(tracker.update(&rules(), &active(9, 10.0), &LIMITS, at(10, 0)).is_empty());
+    assert!(tracker.update(&rules(), &active(9, 20.0), &LIMITS, at(10, 1)).is_empty());
+  }
+
+  #[test]
+  fn reports_a_block_that_starts_later() {
+    let mut tracker = BlockTracker::new();
+    assert!(tracker.update(&rules(), &UsageStats::default(), &LIMITS, at(8, 0)).is_empty());
+    assert_eq!(kinds(&tracker.update(&rules(), &active(9, 1.0), &LIMITS, at(9, 0))), vec![AlertKind::BlockStarted]);
+  }
+
+  #[test]
+  fn warns_once_before_the_block_ends() {
+    let mut tracker = BlockTracker::new();
+    tracker.update(&rules(), &active(9, 10.0), &LIMITS, at(9, 0));
+    assert!(tracker.update(&rules(), &active(9, 10.0), &LIMITS, at(13, 44)).is_empty());
+    assert_eq!(kinds(&tracker.update(&rules(), &active(9, 10.0), &LIMITS, at(13, 45))), vec![AlertKind::BlockEndingSoon]);
+    assert!(tracker.update(&rules(), &active(9, 10.0), &LIMITS, at(13, 50)).is_empty());
+  }
+
+  #[test]
+  fn reports_the_reset_of_a_block_that_hit_its_limit() {
+    let mut tracker = BlockTracker::new();
+    tracker.update(&rules(), &active(9, 100.0), &LIMITS, at(9, 30));
+    assert_eq!(kinds(&tracker.update(&rules(), &UsageStats::default(), &LIMITS, at(14, 0))), vec![AlertKind::BlockReset]);
+  }
+
+  #[test]
+  fn a_block_under_its_limit_resets_quietly() {
+    let mut tracker = BlockTracker::new();
+    tracker.update(&rules(), &active(9, 80.0), &LIMITS, at(9, 30));
+    assert!(tracker.update(&rules(), &UsageStats::default(), &LIMITS, at(14, 0)).is_empty());
+  }
+
+  #[test]
+  fn passing_a_detected_limit_is_not_a_hit() {
+    let mut tracker = BlockTracker::new();
+    tracker.update(&rules(), &active(9, 120.0), &DETECTED, at(9, 30));
+    assert!(tracker.update(&rules(), &UsageStats::default(), &DETECTED, at(14, 0)).is_empty());
+  }
+
+  #[test]
+  fn a_block_budget_counts_as_a_hit_even_with_a_detected_limit() {
+    let mut tracker = BlockTracker::new();
+    let rules = AlertRules { block_budget_usd: Some(5.0), ..rules() };
+    tracker.update(&rules, &UsageStats { session_cost: 6.0, ..active(9, 50.0) }, &DETECTED, at(9, 30));
+    assert_eq!(kinds(&tracker.update(&rules, &UsageStats::default(), &DETECTED, at(14, 0))), vec![AlertKind::BlockReset]);
+  }
+}
@@ -0,0 +1,34 @@
+use crate::domain::entities::{AlertRules, BudgetState, BudgetStatus, UsageStats};
+use super::alerts::PeriodCosts;
+
+/// Spend against every budget the rules define, in day, block, week, month
+/// order. Week and month budgets are skipped while their cost is unknown.
+pub fn budget_statuses(rules: &AlertRules, stats: &UsageStats, period_costs: &PeriodCosts) -> Vec<BudgetStatus> {
+  let budgets = [
+    ("day", rules.daily_budget_usd, Some(stats.cost)),
+    ("block", rules.block_budget_usd, Some(stats.session_cost)),
+    ("week", rules.weekly_budget_usd, period_costs.weekly_cost),
+    ("month", rules.monthly_budget_usd, period_costs.monthly_cost),
+  ];
+  budgets.into_iter()
+    .filter_map(|(period, limit, spent)| {
+      let (limit, spent) = (limit.filter(|limit| *limit > 0.0)?, spent?);
+      let percent = spent / limit * 100.0;
+      let state = if percent >= 100.0 {
+        BudgetState::Exceeded
+      } else if percent >= rules.budget_warning_percent {
+        BudgetState::Warning
+      } else {
+        BudgetState::Ok
+      };
+      Some(BudgetStatus {
+        period: period.to_string(),
+        limit_usd: limit,
+        spent_usd: spent,
+        remaining_usd: (limit - spent).max(0.0),
+        percent,
+        state,
+      })
+    })
+    .collect()
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use crate::domain::{
+  entities::{
+    BreakdownEntry, DailyUsage, HistoryPoint, PeriodComparison, PeriodTotals, SessionUsage, TokenBreakdown, UsageBreakdown,
+    UsageHistory,
+  },
+  repository::UsageRepository,
+};
+
+/// Periods the dashboard understands: the summary periods plus rolling windows.
+pub const PERIODS: [&str; 5] = ["day", "week", "month", "30d", "90d"];
+
+const UNATTRIBUTED: &str = "Unattributed";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateRange {
+  pub start: NaiveDate,
+  pub end: NaiveDate,
+}
+
+impl DateRange {
+  pub fn contains(&self, date: NaiveDate) -> bool {
+    date >= self.start && date <= self.end
+  }
+
+  pub fn days(&self) -> u32 {
+    (self.end - self.start).num_days() as u32 + 1
+  }
+
+  fn contains_str(&self, date: &str) -> bool {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok_and(|date| self.contains(date))
+  }
+}
+
+/// Same boundaries as the usage summary: `week` is the last 7 days and
+/// `month` runs from the 1st to today.
+pub fn period_range(period: &str, today: NaiveDate) -> Result<DateRange, String> {
+  let start = match period {
+    "day" => today,
+    "week" => today - Days::new(6),
+    "month" => today.with_day(1).ok_or("invalid month")?,
+    "30d" => today - Days::new(29),
+    "90d" => today - Days::new(89),
+    _ => return Err(format!("Unknown period '{}', expected one of {}", period, PERIODS.join(", "))),
+  };
+  Ok(DateRange { start, end: today })
+}
+
+/// The period the current one is compared against. Month-to-date is set
+/// against the same days of the previous month; the others against the
+/// equally long stretch right before them.
+pub fn previous_range(period: &str, today: NaiveDate) -> Result<DateRange, String> {
+  let current = period_range(period, today)?;
+  if period == "month" {
+    let start = current.start - Months::new(1);
+    let last_day = current.start - Days::new(1);
+    let end = start.with_day(today.day()).unwrap_or(last_day).min(last_day);
+    return Ok(DateRange { start, end });
+  }
+  let end = current.start - Days::new(1);
+  Ok(DateRange { start: end - Days::new(current.days() as u64 - 1), end })
+}
+
+pub fn build_history(days: &[DailyUsage], period: &str, range: DateRange) -> UsageHistory {
+  let by_date: BTreeMap<&str, &DailyUsage> = days.iter().map(|day| (day.date.as_str(), day)).collect();
+  let points: Vec<HistoryPoint> = range.start.iter_days()
+    .take_while(|date| *date <= range.end)
+    .map(|date| {
+      let date = date.format("%Y-%m-%d").to_string();
+      let (tokens, cost_usd) = by_date.get(date.as_str()).map_or((TokenBreakdown::default(), 0.0), |day| (day.tokens, day.cost_usd));
+      HistoryPoint { date, total_tokens: tokens.total(), tokens, cost_usd }
+    })
+    .collect();
+  let peak_date = points.iter()
+    .filter(|point| point.cost_usd > 0.0)
+    .max_by(|a, b| a.cost_usd.total_cmp(&b.cost_usd))
+    .map(|point| point.date.clone());
+
+  UsageHistory {
+    period: period.to_string(),
+    start_date: range.start.format("%Y-%m-%d").to_string(),
+    end_date: range.end.format("%Y-%m-%d").to_string(),
+    total_tokens: points.iter().map(|point| point.total_tokens).sum(),
+    total_cost_usd: points.iter().map(|point| point.cost_usd).sum(),
+    points,
+    peak_date,
+  }
+}
+
+/// Per-model totals. Days reported without a model split, as older ccusage
+/// versions do, are kept under "Unattributed" so the totals still add up.
+pub fn model_breakdown(days: &[DailyUsage], period: &str, range: DateRange) -> UsageBreakdown {
+  let mut entries: BTreeMap<String, BreakdownEntry> = BTreeMap::new();
+  for day in days.iter().filter(|day| range.contains_str(&day.date)) {
+    if day.models.is_empty() {
+      if day.tokens.total() > 0 || day.cost_usd > 0.0 {
+        accumulate(&mut entries, UNATTRIBUTED, UNATTRIBUTED, &day.tokens, day.cost_usd);
+      }
+      continue;
+    }
+    for model in &day.models {
+      accumulate(&mut entries, &model.model, &model.model, &model.tokens, model.cost_usd);
+    }
+  }
+  finish_breakdown(entries, period, range)
+}
+
+/// Per-project totals. ccusage reports sessions as a whole, so a session
+/// counts towards the period its last activity falls in.
+pub fn project_breakdown(sessions: &[SessionUsage], period: &str, range: DateRange) -> UsageBreakdown {
+  let mut entries: BTreeMap<String, BreakdownEntry> = BTreeMap::new();
+  for session in sessions.iter().filter(|session| range.contains_str(&session.last_activity)) {
+    accumulate(&mut entries, &session.project_path, project_name(&session.project_path), &session.tokens, session.cost_usd);
+  }
+  finish_breakdown(entries, period, range)
+}
+
+pub fn compare_periods(days: &[DailyUsage], period: &str, current: DateRange, previous: DateRange) -> PeriodComparison {
+  let current = period_totals(days, current);
+  let previous = period_totals(days, previous);
+  PeriodComparison {
+    period: period.to_string(),
+    tokens_change_percent: change_percent(current.total_tokens as f64, previous.total_tokens as f64),
+    cost_change_percent: change_percent(current.cost_usd, previous.cost_usd),
+    current,
+    previous,
+  }
+}
+
+/// The daily-report views take the report as an argument so the dashboard's
+/// parallel requests can share one cached ccusage run.
+pub fn get_usage_history(days: &[DailyUsage], period: &str) -> Result<UsageHistory, String> {
+  let range = period_range(period, Local::now().date_naive())?;
+  Ok(build_history(days, period, range))
+}
+
+pub fn get_model_breakdown(days: &[DailyUsage], period: &str) -> Result<UsageBreakdown, String> {
+  let range = period_range(period, Local::now().date_naive())?;
+  Ok(model_breakdown(days, period, range))
+}
+
+pub async fn get_project_breakdown(repo: Arc<dyn UsageRepository + Send + Sync>, period: String) -> Result<UsageBreakdown, String> {
+  let range = period_range(&period, Local::now().date_naive())?;
+  let sessions = repo.get_sessions().await?;
+  Ok(project_breakdown(&sessions, &period, range))
+}
+
+pub fn get_period_comparison(days: &[DailyUsage], period: &str) -> Result<PeriodComparison, String> {
+  let today = Local::now().date_naive();
+  let (current, previous) = (period_range(period, today)?, previous_range(period, today)?);
+  Ok(compare_periods(days, period, current, previous))
+}
+
+fn accumulate(entries: &mut BTreeMap<String, BreakdownEntry>, key: &str, name: &str, tokens: &TokenBreakdown, cost_usd: f64) {
+  let entry = entries.entry(key.to_string()).or_insert_with(|| BreakdownEntry {
+    name: name.to_string(),
+    key: key.to_string(),
+    tokens: TokenBreakdown::default(),
+    total_tokens: 0,
+    cost_usd: 0.0,
+    cost_share: 0.0,
+    count: 0,
+  });
+  entry.tokens.add(tokens);
+  entry.cost_usd += cost_usd;
+  entry.count += 1;
+}
+
+fn finish_breakdown(entries: BTreeMap<String, BreakdownEntry>, period: &str, range: DateRange) -> UsageBreakdown {
+  let total_cost_usd: f64 = entries.values().map(|entry| entry.cost_usd).sum();
+  let mut entries: Vec<BreakdownEntry> = entries.into_values()
+    .map(|mut entry| {
+      entry.total_tokens = entry.tokens.total();
+      entry.cost_share = if total_cost_usd > 0.0 { entry.cost_usd / total_cost_usd * 100.0 } else { 0.0 };
+      entry
+    })
+    .collect();
+  entries.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd).then(b.total_tokens.cmp(&a.total_tokens)));
+
+  UsageBreakdown {
+    period: period.to_string(),
+    start_date: range.start.format("%Y-%m-%d").to_string(),
+    end_date: range.end.format("%Y-%m-%d").to_string(),
+    total_tokens: entries.iter().map(|entry| entry.total_tokens).sum(),
+    total_cost_usd,
+    entries,
+  }
+}
+
+fn period_totals(days: &[DailyUsage], range: DateRange) -> PeriodTotals {
+  let mut tokens = TokenBreakdown::default();
+  let mut cost_usd = 0.0;
+  for day in days.iter().filter(|day| range.contains_str(&day.date)) {
+    tokens.add(&day.tokens);
+    cost_usd += day.cost_usd;
+  }
+  PeriodTotals {
+    start_date: range.start.format("%Y-%m-%d").to_string(),
+    end_date: range.end.format("%Y-%m-%d").to_string(),
+    days: range.days(),
+    total_tokens: tokens.total(),
+    tokens,
+    cost_usd,
+    avg_cost_per_day: cost_usd / range.days() as f64,
+  }
+}
+
+fn change_percent(current: f64, previous: f64) -> Option<f64> {
+  (previous > 0.0).then(|| (current - previous) / previous * 100.0)
+}
+
+/// Last path component, since Claude stores projects under their full path.
+fn project_name(project_path: &str) -> &str {
+  project_path.trim_end_matches(['/', '\\'])
+    .rsplit(['/', '\\'])
+    .find(|segment| !segment.is_empty())
+    .unwrap_or(project_path)
+}
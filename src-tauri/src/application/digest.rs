@@ -0,0 +1,181 @@
+use std::sync::Arc;
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Timelike};
+use crate::domain::{
+  entities::{DigestSchedule, SmtpConfig, UsagePeriodSummary},
+  repository::UsageRepository,
+  settings::CurrencyConfig,
+};
+use super::use_cases;
+
+pub struct Digest {
+  pub subject: String,
+  pub body: String,
+}
+
+/// Whether a digest should go out at `now`, given the day the last one was
+/// sent. A weekly digest that was missed while the app was not running goes
+/// out late rather than waiting another week; a missed daily one is simply
+/// replaced by the next.
+pub fn is_digest_due(config: &SmtpConfig, now: DateTime<Local>, last_sent: Option<NaiveDate>) -> bool {
+  let today = now.date_naive();
+  let Some(slot) = latest_slot(config, now) else {
+    return false;
+  };
+  match last_sent {
+    Some(last_sent) => last_sent < slot,
+    // Nothing to catch up on before the first digest.
+    None => slot == today,
+  }
+}
+
+/// The day of the most recent scheduled digest at or before `now`.
+fn latest_slot(config: &SmtpConfig, now: DateTime<Local>) -> Option<NaiveDate> {
+  let today = now.date_naive();
+  let sent_today = now.hour() >= config.digest_hour;
+  match config.digest {
+    DigestSchedule::Off => None,
+    DigestSchedule::Daily => sent_today.then_some(today),
+    DigestSchedule::Weekly => {
+      let days_since = (today.weekday().num_days_from_monday() + 7 - config.digest_weekday % 7) % 7;
+      let days_since = if days_since == 0 && !sent_today { 7 } else { days_since };
+      Some(today - Days::new(days_since as u64))
+    }
+  }
+}
+
+pub async fn build_digest(
+  repo: Arc<dyn UsageRepository + Send + Sync>,
+  schedule: DigestSchedule,
+  currency: &CurrencyConfig,
+) -> Result<Digest, String> {
+  let periods = match schedule {
+    DigestSchedule::Weekly => ["week", "month"],
+    _ => ["day", "week"],
+  };
+
+  let mut summaries = Vec::new();
+  for period in periods {
+    summaries.push(use_cases::get_usage_summary(repo.clone(), period.to_string()).await?);
+  }
+  Ok(render_digest(schedule, &summaries, currency))
+}
+
+/// Subject and body for the summaries `build_digest` collected, headline first.
+fn render_digest(schedule: DigestSchedule, summaries: &[UsagePeriodSummary], currency: &CurrencyConfig) -> Digest {
+  let label = match schedule {
+    DigestSchedule::Weekly => "Weekly",
+    _ => "Daily",
+  };
+  let headline = &summaries[0];
+  let subject = format!(
+    "[Claude Token Monitor] {} summary: {}, {} tokens",
+    label, currency.format(headline.total_cost), headline.total_tokens
+  );
+
+  let mut body = format!("Claude usage {} summary\n", label.to_lowercase());
+  for summary in summaries {
+    body.push_str(&format_summary(summary, currency));
+  }
+  body.push_str("\n-- \nSent by Claude Token Monitor\n");
+
+  Digest { subject, body }
+}
+
+fn format_summary(summary: &UsagePeriodSummary, currency: &CurrencyConfig) -> String {
+  let title = match summary.period.as_str() {
+    "week" => "Last 7 days",
+    "month" => "This month",
+    _ => "Today",
+  };
+  format!(
+    "\n{} ({} to {}, {} day(s))\n  Total tokens:   {}\n  Total cost:     {}\n  Avg tokens/day: {:.0}\n  Avg cost/day:   {}\n",
+    title,
+    summary.start_date,
+    summary.end_date,
+    summary.days,
+    summary.total_tokens,
+    currency.format(summary.total_cost),
+    summary.avg_tokens_per_day,
+    currency.format(summary.avg_cost_per_day),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::TimeZone;
+  use super::*;
+
+  fn config(digest: DigestSchedule) -> SmtpConfig {
+    // Weekly digests go out on Fridays at 18:00.
+    SmtpConfig { digest, digest_hour: 18, digest_weekday: 4, ..SmtpConfig::default() }
+  }
+
+  fn at(day: u32, hour: u32) -> DateTime<Local> {
+    // 2026-10-12 is a Monday.
+    Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap()
+  }
+
+  fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+  }
+
+  fn summary(period: &str, total_tokens: u64, total_cost: f32) -> UsagePeriodSummary {
+    UsagePeriodSummary {
+      period: period.to_string(),
+      start_date: "2026-10-10".to_string(),
+      end_date: "2026-10-16".to_string(),
+      days: 7,
+      total_tokens,
+      total_cost,
+      avg_tokens_per_day: total_tokens as f32 / 7.0,
+      avg_cost_per_day: total_cost / 7.0,
+    }
+  }
+
+  #[test]
+  fn daily_digest_goes_out_once_after_the_hour() {
+    let config = config(DigestSchedule::Daily);
+    assert!(!is_digest_due(&config, at(14, 17), Some(date(13))));
+    assert!(is_digest_due(&config, at(14, 18), Some(date(13))));
+    assert!(!is_digest_due(&config, at(14, 22), Some(date(14))));
+    assert!(is_digest_due(&config, at(14, 18), None));
+  }
+
+  #[test]
+  fn weekly_digest_waits_for_its_day() {
+    let config = config(DigestSchedule::Weekly);
+    assert!(!is_digest_due(&config, at(15, 20), Some(date(9))));
+    assert!(!is_digest_due(&config, at(16, 17), Some(date(9))));
+    assert!(is_digest_due(&config, at(16, 18), Some(date(9))));
+    assert!(!is_digest_due(&config, at(16, 19), Some(date(16))));
+  }
+
+  #[test]
+  fn missed_weekly_digest_is_caught_up() {
+    let config = config(DigestSchedule::Weekly);
+    // Down all Friday: the digest goes out on Monday instead.
+    assert!(is_digest_due(&config, at(19, 9), Some(date(9))));
+    assert!(!is_digest_due(&config, at(19, 10), Some(date(19))));
+    // Without a previous digest there is nothing to catch up on.
+    assert!(!is_digest_due(&config, at(19, 9), None));
+  }
+
+  #[test]
+  fn renders_headline_and_every_period() {
+    let summaries = [summary("week", 1_234_567, 42.5), summary("month", 2_000_000, 80.0)];
+    let digest = render_digest(DigestSchedule::Weekly, &summaries, &CurrencyConfig::default());
+    assert_eq!(digest.subject, "[Claude Token Monitor] Weekly summary: $42.50, 1234567 tokens");
+    assert!(digest.body.starts_with("Claude usage weekly summary\n"));
+    assert!(digest.body.contains("\nLast 7 days (2026-10-10 to 2026-10-16, 7 day(s))\n  Total tokens:   1234567\n  Total cost:     $42.50\n"));
+    assert!(digest.body.contains("\nThis month ("));
+    assert!(digest.body.ends_with("Sent by Claude Token Monitor\n"));
+  }
+
+  #[test]
+  fn renders_costs_in_the_profile_currency() {
+    let euro = CurrencyConfig { code: "EUR".to_string(), symbol: "€".to_string(), usd_rate: 0.5 };
+    let digest = render_digest(DigestSchedule::Daily, &[summary("day", 1_000, 10.0)], &euro);
+    assert!(digest.subject.ends_with("Daily summary: €5.00, 1000 tokens"), "{}", digest.subject);
+    assert!(digest.body.contains("  Total cost:     €5.00\n"), "{}", digest.body);
+  }
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Duration};
+use crate::domain::entities::{DetectedBlockLimit, LimitConfidence, UsageBlock};
+
+/// A block whose last entry came at least this long before its scheduled
+/// end stopped early.
+const STOP_BEFORE_END_MINUTES: i64 = 30;
+/// Usage has to have run this long for an early stop to look like a rate
+/// limit rather than the end of a short session.
+const SUSTAINED_MINUTES: i64 = 60;
+/// The block also has to reach this share of the heaviest one.
+const SUSTAINED_TOKEN_RATIO: f64 = 0.5;
+
+/// Same idea as `ccusage blocks --token-limit max`: the highest completed
+/// block is the best known ceiling. Confidence grows with the number of
+/// blocks that hit a rate-limit stop, since one heavy block alone may not
+/// have reached the real limit.
+pub fn detect_block_limit(blocks: &[UsageBlock]) -> Option<DetectedBlockLimit> {
+  let completed: Vec<&UsageBlock> = blocks.iter()
+    .filter(|block| !block.is_active && !block.is_gap && block.total_tokens > 0)
+    .collect();
+  let max_block = completed.iter().max_by_key(|block| block.total_tokens)?;
+
+  let max_tokens = max_block.total_tokens as u64;
+  let limit_hits = completed.iter()
+    .filter(|block| block.total_tokens as f64 >= max_tokens as f64 * SUSTAINED_TOKEN_RATIO && stopped_early(block))
+    .count() as u32;
+  let confidence = match limit_hits {
+    0..=1 => LimitConfidence::Low,
+    2 => LimitConfidence::Medium,
+    _ => LimitConfidence::High,
+  };
+
+  Some(DetectedBlockLimit {
+    block_tokens: max_tokens,
+    confidence,
+    completed_blocks: completed.len() as u32,
+    limit_hits,
+    max_block_start_time: max_block.start_time.clone(),
+  })
+}
+
+/// Whether usage ran for a while and then stopped well before the block's
+/// scheduled end, the way it does when the rate limit cuts a session off.
+fn stopped_early(block: &UsageBlock) -> bool {
+  let parse = |time: &str| DateTime::parse_from_rfc3339(time).ok();
+  let (Some(start), Some(end), Some(actual_end)) = (
+    parse(&block.start_time),
+    parse(&block.end_time),
+    block.actual_end_time.as_deref().and_then(parse),
+  ) else {
+    return false;
+  };
+  actual_end - start >= Duration::minutes(SUSTAINED_MINUTES) && end - actual_end >= Duration::minutes(STOP_BEFORE_END_MINUTES)
+}
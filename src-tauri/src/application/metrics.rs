@@ -0,0 +1,143 @@
+use std::fmt::Write;
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use crate::domain::entities::{BudgetStatus, DailyUsage, TokenBreakdown, UsageSnapshot};
+use super::alerts::PeriodCosts;
+
+/// Everything one scrape or textfile write reports.
+pub struct MetricsInput {
+  pub snapshot: UsageSnapshot,
+  /// Today's entry from the daily report, for the per-model split.
+  pub today: Option<DailyUsage>,
+  pub week_cost: Option<f32>,
+  pub month_cost: Option<f32>,
+  pub budgets: Vec<BudgetStatus>,
+}
+
+/// Week (the last 7 days) and month-to-date spend from the daily report,
+/// matching the periods of `get_usage_summary`.
+pub fn period_costs(daily: &[DailyUsage], today: NaiveDate) -> PeriodCosts {
+  let week_start = today.checked_sub_days(Days::new(6)).unwrap_or(today);
+  let month_start = today.with_day(1).unwrap_or(today);
+  let cost_since = |start: NaiveDate| daily.iter()
+    .filter(|day| NaiveDate::parse_from_str(&day.date, "%Y-%m-%d").is_ok_and(|date| date >= start && date <= today))
+    .map(|day| day.cost_usd as f32)
+    .sum::<f32>();
+  PeriodCosts { weekly_cost: Some(cost_since(week_start)), monthly_cost: Some(cost_since(month_start)) }
+}
+
+/// Renders the Prometheus text exposition format (version 0.0.4).
+/// Every series carries a `profile` label so several profiles or machines
+/// can share one dashboard.
+pub fn render(input: &MetricsInput, now: DateTime<Utc>) -> String {
+  let mut out = Exposition::default();
+  let stats = &input.snapshot.stats;
+  let profile = input.snapshot.profile.as_str();
+
+  out.family("claude_tokens_today", "Tokens used today by model and token class.");
+  if let Some(today) = &input.today {
+    for model in &today.models {
+      for (class, tokens) in token_classes(&model.tokens) {
+        out.sample("claude_tokens_today", &[("profile", profile), ("model", &model.model), ("class", class)], tokens as f64);
+      }
+    }
+  }
+
+  out.family("claude_cost_today_usd", "Cost today by model in US dollars.");
+  if let Some(today) = &input.today {
+    for model in &today.models {
+      out.sample("claude_cost_today_usd", &[("profile", profile), ("model", &model.model)], model.cost_usd);
+    }
+  }
+
+  out.family("claude_cost_usd", "Cost in US dollars for the current day, block, week and month.");
+  let costs = [
+    ("day", Some(stats.cost)),
+    ("block", Some(stats.session_cost)),
+    ("week", input.week_cost),
+    ("month", input.month_cost),
+  ];
+  for (period, cost) in costs {
+    if let Some(cost) = cost {
+      out.sample("claude_cost_usd", &[("profile", profile), ("period", period)], cost as f64);
+    }
+  }
+
+  out.family("claude_block_active", "1 while a 5-hour block is active.");
+  out.sample("claude_block_active", &[("profile", profile)], if stats.active_session { 1.0 } else { 0.0 });
+  out.family("claude_block_tokens", "Tokens used in the active block.");
+  out.sample("claude_block_tokens", &[("profile", profile)], stats.current_tokens as f64);
+
+  out.family("claude_block_remaining_seconds", "Seconds until the active block resets.");
+  let remaining = stats.block_end_time.as_deref()
+    .and_then(|end| DateTime::parse_from_rfc3339(end).ok())
+    .map(|end| (end.with_timezone(&Utc) - now).num_seconds().max(0) as f64)
+    .or(stats.block_minutes_remaining.map(|minutes| minutes as f64 * 60.0));
+  if let (true, Some(remaining)) = (stats.active_session, remaining) {
+    out.sample("claude_block_remaining_seconds", &[("profile", profile)], remaining);
+  }
+
+  out.family("claude_burn_rate_tokens_per_minute", "Token burn rate in the active block.");
+  if let Some(burn_rate) = stats.burn_rate {
+    out.sample("claude_burn_rate_tokens_per_minute", &[("profile", profile)], burn_rate as f64);
+  }
+
+  out.family("claude_plan_limit_ratio", "Tokens used against the plan limit, 1 = limit reached.");
+  let limits = [("block", stats.percent_of_block_limit), ("week", stats.percent_of_weekly_limit)];
+  for (window, percent) in limits {
+    if let Some(percent) = percent {
+      out.sample("claude_plan_limit_ratio", &[("profile", profile), ("window", window)], percent as f64 / 100.0);
+    }
+  }
+
+  out.family("claude_budget_limit_usd", "Configured budget in US dollars.");
+  for budget in &input.budgets {
+    out.sample("claude_budget_limit_usd", &[("profile", profile), ("period", &budget.period)], budget.limit_usd as f64);
+  }
+  out.family("claude_budget_utilisation_ratio", "Spend against the configured budget, 1 = budget used up.");
+  for budget in &input.budgets {
+    out.sample("claude_budget_utilisation_ratio", &[("profile", profile), ("period", &budget.period)], budget.percent as f64 / 100.0);
+  }
+
+  out.family("claude_last_poll_timestamp_seconds", "Unix time of the poll these metrics come from.");
+  if let Ok(updated_at) = DateTime::parse_from_rfc3339(&input.snapshot.updated_at) {
+    out.sample("claude_last_poll_timestamp_seconds", &[("profile", profile)], updated_at.timestamp() as f64);
+  }
+
+  out.text
+}
+
+fn token_classes(tokens: &TokenBreakdown) -> [(&'static str, u64); 4] {
+  [
+    ("input", tokens.input_tokens),
+    ("output", tokens.output_tokens),
+    ("cache_creation", tokens.cache_creation_tokens),
+    ("cache_read", tokens.cache_read_tokens),
+  ]
+}
+
+#[derive(Default)]
+struct Exposition {
+  text: String,
+}
+
+impl Exposition {
+  /// Every metric here is a gauge: the daily and block values reset on their own.
+  fn family(&mut self, name: &str, help: &str) {
+    let _ = writeln!(self.text, "# HELP {} {}", name, help);
+    let _ = writeln!(self.text, "# TYPE {} gauge", name);
+  }
+
+  fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+    if !value.is_finite() {
+      return;
+    }
+    let labels: Vec<String> = labels.iter()
+      .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+      .collect();
+    let _ = writeln!(self.text, "{}{{{}}} {}", name, labels.join(","), value);
+  }
+}
+
+fn escape_label(value: &str) -> String {
+  value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -1 +1,9 @@
+pub mod alerts;
+pub mod block_tracker;
+pub mod budgets;
+pub mod dashboard;
+pub mod digest;
+pub mod limit_detection;
+pub mod metrics;
+pub mod status_template;
 pub mod use_cases;
\ No newline at end of file
@@ -0,0 +1,181 @@
+use chrono::DateTime;
+use crate::domain::{entities::UsageStats, settings::CurrencyConfig};
+
+/// Placeholders available in status templates such as the tray tooltip.
+pub const FIELDS: &[&str] = &[
+  "session_cost",
+  "daily_cost",
+  "burn_rate",
+  "session_tokens",
+  "daily_tokens",
+  "weekly_tokens",
+  "block_percent",
+  "weekly_percent",
+  "time_left",
+  "reset_time",
+  "model",
+  "profile",
+];
+
+/// Shown for values that are unknown, e.g. `time_left` without an active block.
+const MISSING: &str = "-";
+
+pub struct TemplateContext<'a> {
+  pub stats: &'a UsageStats,
+  pub currency: &'a CurrencyConfig,
+  pub profile: &'a str,
+}
+
+enum Segment<'a> {
+  Literal(String),
+  Field { name: &'a str, spec: Spec },
+}
+
+#[derive(Default)]
+struct Spec {
+  /// `$`: format a cost in the profile's currency.
+  currency: bool,
+  /// `k`: abbreviate large numbers as 12.3k or 4.5M.
+  compact: bool,
+  /// `.N`: number of decimals.
+  decimals: Option<usize>,
+}
+
+enum FieldValue {
+  Cost(f32),
+  Number(f64),
+  Percent(Option<f32>),
+  Text(Option<String>),
+}
+
+/// Checks that every placeholder names a known field with a valid format.
+pub fn validate_template(template: &str) -> Result<(), String> {
+  for segment in parse(template)? {
+    if let Segment::Field { name, .. } = segment {
+      if !FIELDS.contains(&name) {
+        return Err(format!("unknown placeholder '{{{}}}', expected one of: {}", name, FIELDS.join(", ")));
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Renders `{field}` / `{field:spec}` placeholders; `{{` and `}}` are literal braces.
+/// Invalid templates render as-is so a typo never blanks the tray.
+pub fn render_template(template: &str, context: &TemplateContext) -> String {
+  let Ok(segments) = parse(template) else {
+    return template.to_string();
+  };
+  segments.into_iter()
+    .map(|segment| match segment {
+      Segment::Literal(text) => text,
+      Segment::Field { name, spec } => match field_value(name, context) {
+        Some(value) => format_value(value, &spec, context.currency),
+        None => format!("{{{}}}", name),
+      },
+    })
+    .collect()
+}
+
+fn parse(template: &str) -> Result<Vec<Segment<'_>>, String> {
+  let mut segments = Vec::new();
+  let mut literal = String::new();
+  let mut rest = template;
+  while let Some(index) = rest.find(['{', '}']) {
+    literal.push_str(&rest[..index]);
+    let brace = &rest[index..index + 1];
+    rest = &rest[index + 1..];
+    if rest.starts_with(brace) {
+      literal.push_str(brace);
+      rest = &rest[1..];
+      continue;
+    }
+    if brace == "}" {
+      return Err("unmatched '}' (use '}}' for a literal brace)".to_string());
+    }
+    let end = rest.find('}').ok_or("unclosed '{' (use '{{' for a literal brace)")?;
+    let placeholder = &rest[..end];
+    rest = &rest[end + 1..];
+    let (name, spec) = placeholder.split_once(':').unwrap_or((placeholder, ""));
+    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+    segments.push(Segment::Field { name: name.trim(), spec: parse_spec(spec)? });
+  }
+  literal.push_str(rest);
+  segments.push(Segment::Literal(literal));
+  Ok(segments)
+}
+
+fn parse_spec(spec: &str) -> Result<Spec, String> {
+  let mut parsed = Spec::default();
+  let mut rest = spec.trim();
+  if let Some(after) = rest.strip_prefix('$') {
+    parsed.currency = true;
+    rest = after;
+  } else if let Some(after) = rest.strip_prefix('k') {
+    parsed.compact = true;
+    rest = after;
+  }
+  if let Some(decimals) = rest.strip_prefix('.') {
+    parsed.decimals = Some(decimals.parse().map_err(|_| format!("invalid format '{}'", spec))?);
+  } else if !rest.is_empty() {
+    return Err(format!("invalid format '{}', expected '$', 'k' and/or '.N'", spec));
+  }
+  Ok(parsed)
+}
+
+fn field_value(name: &str, context: &TemplateContext) -> Option<FieldValue> {
+  let stats = context.stats;
+  let value = match name {
+    "session_cost" => FieldValue::Cost(stats.session_cost),
+    "daily_cost" => FieldValue::Cost(stats.cost),
+    "burn_rate" => FieldValue::Number(stats.burn_rate.unwrap_or(0.0) as f64),
+    "session_tokens" => FieldValue::Number(stats.current_tokens as f64),
+    "daily_tokens" => FieldValue::Number(stats.daily_tokens as f64),
+    "weekly_tokens" => FieldValue::Number(stats.weekly_tokens as f64),
+    "block_percent" => FieldValue::Percent(stats.percent_of_block_limit),
+    "weekly_percent" => FieldValue::Percent(stats.percent_of_weekly_limit),
+    "time_left" => FieldValue::Text(stats.block_minutes_remaining.filter(|_| stats.active_session).map(format_duration)),
+    "reset_time" => FieldValue::Text(
+      stats.block_end_time.as_deref()
+        .filter(|_| stats.active_session)
+        .and_then(|end| DateTime::parse_from_rfc3339(end).ok())
+        .map(|end| end.with_timezone(&chrono::Local).format("%H:%M").to_string()),
+    ),
+    "model" => FieldValue::Text(Some(stats.model.clone()).filter(|model| !model.is_empty())),
+    "profile" => FieldValue::Text(Some(context.profile.to_string())),
+    _ => return None,
+  };
+  Some(value)
+}
+
+fn format_value(value: FieldValue, spec: &Spec, currency: &CurrencyConfig) -> String {
+  match value {
+    FieldValue::Cost(usd) => {
+      let amount = format!("{:.*}", spec.decimals.unwrap_or(2), currency.convert(usd));
+      if spec.currency { format!("{}{}", currency.symbol, amount) } else { amount }
+    }
+    FieldValue::Number(number) if spec.compact => format_compact(number, spec.decimals.unwrap_or(1)),
+    FieldValue::Number(number) => format!("{:.*}", spec.decimals.unwrap_or(0), number),
+    FieldValue::Percent(Some(percent)) => format!("{:.*}%", spec.decimals.unwrap_or(0), percent),
+    FieldValue::Percent(None) | FieldValue::Text(None) => MISSING.to_string(),
+    FieldValue::Text(Some(text)) => text,
+  }
+}
+
+fn format_compact(number: f64, decimals: usize) -> String {
+  match number.abs() {
+    n if n >= 1_000_000_000.0 => format!("{:.*}B", decimals, number / 1_000_000_000.0),
+    n if n >= 1_000_000.0 => format!("{:.*}M", decimals, number / 1_000_000.0),
+    n if n >= 1_000.0 => format!("{:.*}k", decimals, number / 1_000.0),
+    _ => format!("{:.0}", number),
+  }
+}
+
+/// `2h 05m` for longer spans, `45m` below an hour.
+pub fn format_duration(minutes: u32) -> String {
+  if minutes >= 60 {
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+  } else {
+    format!("{}m", minutes)
+  }
+}
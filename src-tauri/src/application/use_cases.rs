@@ -1,13 +1,45 @@
 use std::sync::Arc;
 use crate::domain::{
-  entities::{UsageStats, UsagePeriodSummary},
+  entities::{Alert, DetectedBlockLimit, PlanLimits, UsageBlock, UsageStats, UsagePeriodSummary},
+  notifier::Notifier,
   repository::UsageRepository,
 };
+use super::limit_detection;
 
-pub async fn get_claude_usage(repo: Arc<dyn UsageRepository + Send + Sync>) -> Result<UsageStats, String> {
-  repo.get_claude_usage().await
+pub async fn get_claude_usage(repo: Arc<dyn UsageRepository + Send + Sync>, limits: &PlanLimits) -> Result<UsageStats, String> {
+  let mut stats = repo.get_claude_usage().await?;
+  apply_plan_limits(&mut stats, limits);
+  Ok(stats)
+}
+
+pub async fn get_blocks(repo: Arc<dyn UsageRepository + Send + Sync>) -> Result<Vec<UsageBlock>, String> {
+  repo.get_blocks().await
+}
+
+pub async fn detect_block_limit(repo: Arc<dyn UsageRepository + Send + Sync>) -> Result<Option<DetectedBlockLimit>, String> {
+  let blocks = repo.get_blocks().await?;
+  Ok(limit_detection::detect_block_limit(&blocks))
+}
+
+pub fn apply_plan_limits(stats: &mut UsageStats, limits: &PlanLimits) {
+  stats.percent_of_block_limit = limits.block_tokens
+    .filter(|limit| *limit > 0)
+    .map(|limit| stats.current_tokens as f32 / limit as f32 * 100.0);
+  stats.percent_of_weekly_limit = limits.weekly_tokens
+    .filter(|limit| *limit > 0)
+    .map(|limit| stats.weekly_tokens as f32 / limit as f32 * 100.0);
 }
 
 pub async fn get_usage_summary(repo: Arc<dyn UsageRepository + Send + Sync>, period: String) -> Result<UsagePeriodSummary, String> {
   repo.get_usage_summary(period).await
-}
\ No newline at end of file
+}
+
+pub async fn dispatch_alert(notifiers: &[Arc<dyn Notifier + Send + Sync>], alert: &Alert) -> Vec<String> {
+  let mut errors = Vec::new();
+  for notifier in notifiers {
+    if let Err(e) = notifier.notify(alert).await {
+      errors.push(format!("{}: {}", notifier.name(), e));
+    }
+  }
+  errors
+}
@@ -0,0 +1,4 @@
+fn main() {
+  let args = std::env::args().skip(1).collect();
+  std::process::exit(cc_widgetauri_lib::cli::run(args));
+}
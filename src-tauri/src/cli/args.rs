@@ -0,0 +1,236 @@
+pub const USAGE: &str = "\
+Usage: claude-token-monitor <command> [options]
+
+Commands:
+  status                 Current block, today and this week
+  summary [--period P]   Totals for day, week or month (default: day)
+  blocks [--last N]      The last N 5-hour blocks (default: 10)
+  budgets                Spend against the profile's budgets
+  doctor                 Check settings, ccusage and the local API
+  statusline [--no-color]
+                         One line for Claude Code's statusLine; reads its
+                         session JSON on stdin and answers from the app's
+                         last poll
+  bar [--style S] [--follow] [--no-color]
+                         A status bar module: waybar (default), i3blocks,
+                         polybar or tmux; --follow prints a line per poll
+
+Options:
+  -f, --format F         table, json or csv (default: table)
+  -p, --profile NAME     Use this profile instead of the active one
+  -v, --verbose          Log ccusage path discovery to stdout
+  -h, --help             Show this help
+  -V, --version          Show the version";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  Table,
+  Json,
+  Csv,
+}
+
+impl Format {
+  fn parse(value: &str) -> Result<Self, String> {
+    match value {
+      "table" => Ok(Format::Table),
+      "json" => Ok(Format::Json),
+      "csv" => Ok(Format::Csv),
+      _ => Err(format!("format must be table, json or csv, got '{}'", value)),
+    }
+  }
+}
+
+/// The status bar `bar` writes for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarStyle {
+  Waybar,
+  I3blocks,
+  Polybar,
+  Tmux,
+}
+
+impl BarStyle {
+  fn parse(value: &str) -> Result<Self, String> {
+    match value {
+      "waybar" => Ok(BarStyle::Waybar),
+      "i3blocks" => Ok(BarStyle::I3blocks),
+      "polybar" => Ok(BarStyle::Polybar),
+      "tmux" => Ok(BarStyle::Tmux),
+      _ => Err(format!("style must be waybar, i3blocks, polybar or tmux, got '{}'", value)),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+  Status,
+  Summary { period: String },
+  Blocks { last: usize },
+  Budgets,
+  Doctor,
+  Statusline { color: bool },
+  Bar { style: BarStyle, follow: bool, color: bool },
+  Help,
+  Version,
+}
+
+#[derive(Debug, Clone)]
+pub struct Invocation {
+  pub command: Command,
+  pub format: Format,
+  pub profile: Option<String>,
+  pub verbose: bool,
+}
+
+pub fn parse(args: &[String]) -> Result<Invocation, String> {
+  let mut name: Option<String> = None;
+  let mut format = Format::Table;
+  let mut profile = None;
+  let mut period = None;
+  let mut last = None;
+  let mut verbose = false;
+  let mut format_set = false;
+  let mut no_color = false;
+  let mut style = None;
+  let mut follow = false;
+  let mut help = false;
+  let mut version = false;
+
+  let mut args = args.iter();
+  while let Some(arg) = args.next() {
+    // Accept both `--format json` and `--format=json`.
+    let (flag, inline) = match arg.split_once('=') {
+      Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+      _ => (arg.as_str(), None),
+    };
+    let mut value = || inline.clone().or_else(|| args.next().cloned()).ok_or_else(|| format!("{} needs a value", flag));
+    match flag {
+      "-h" | "--help" => help = true,
+      "-V" | "--version" => version = true,
+      "-v" | "--verbose" => verbose = true,
+      "-f" | "--format" => {
+        format = Format::parse(&value()?)?;
+        format_set = true;
+      }
+      "--no-color" => no_color = true,
+      "--style" => style = Some(BarStyle::parse(&value()?)?),
+      "--follow" => follow = true,
+      "-p" | "--profile" => profile = Some(value()?),
+      "--period" => period = Some(value()?),
+      "--last" => last = Some(value()?.parse::<usize>().map_err(|_| "--last needs a whole number".to_string())?),
+      _ if flag.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+      _ if name.is_none() => name = Some(arg.clone()),
+      _ => return Err(format!("unexpected argument '{}'", arg)),
+    }
+  }
+
+  let command = if help {
+    Command::Help
+  } else if version {
+    Command::Version
+  } else {
+    match name.as_deref() {
+      None => Command::Help,
+      Some("status") => Command::Status,
+      Some("summary") => {
+        let period = period.take().unwrap_or_else(|| "day".to_string());
+        if !matches!(period.as_str(), "day" | "week" | "month") {
+          return Err(format!("--period must be day, week or month, got '{}'", period));
+        }
+        Command::Summary { period }
+      }
+      Some("blocks") => Command::Blocks { last: last.take().unwrap_or(10) },
+      Some("budgets") => Command::Budgets,
+      Some("doctor") => Command::Doctor,
+      // Colour is also off when NO_COLOR is set, see https://no-color.org.
+      Some("statusline") => Command::Statusline { color: !no_color && std::env::var_os("NO_COLOR").is_none() },
+      Some("bar") => Command::Bar {
+        style: style.take().unwrap_or(BarStyle::Waybar),
+        follow,
+        color: !no_color && std::env::var_os("NO_COLOR").is_none(),
+      },
+      Some(other) => return Err(format!("unknown command '{}'", other)),
+    }
+  };
+  if period.is_some() && !matches!(command, Command::Summary { .. } | Command::Help) {
+    return Err("--period only applies to summary".to_string());
+  }
+  if last.is_some() && !matches!(command, Command::Blocks { .. } | Command::Help) {
+    return Err("--last only applies to blocks".to_string());
+  }
+  if no_color && !matches!(command, Command::Statusline { .. } | Command::Bar { .. } | Command::Help) {
+    return Err("--no-color only applies to statusline and bar".to_string());
+  }
+  if (style.is_some() || follow) && !matches!(command, Command::Bar { .. } | Command::Help) {
+    return Err("--style and --follow only apply to bar".to_string());
+  }
+  if format_set && matches!(command, Command::Statusline { .. } | Command::Bar { .. }) {
+    return Err("statusline and bar have their own output and take no --format".to_string());
+  }
+  Ok(Invocation { command, format, profile, verbose })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse_line(line: &str) -> Result<Invocation, String> {
+    parse(&line.split_whitespace().map(String::from).collect::<Vec<_>>())
+  }
+
+  #[test]
+  fn no_arguments_shows_help() {
+    assert_eq!(parse_line("").unwrap().command, Command::Help);
+  }
+
+  #[test]
+  fn parses_a_command_with_its_options() {
+    let invocation = parse_line("blocks --last 3 -f csv --profile work -v").unwrap();
+    assert_eq!(invocation.command, Command::Blocks { last: 3 });
+    assert_eq!(invocation.format, Format::Csv);
+    assert_eq!(invocation.profile.as_deref(), Some("work"));
+    assert!(invocation.verbose);
+  }
+
+  #[test]
+  fn accepts_inline_option_values() {
+    let invocation = parse_line("summary --period=month --format=json").unwrap();
+    assert_eq!(invocation.command, Command::Summary { period: "month".to_string() });
+    assert_eq!(invocation.format, Format::Json);
+  }
+
+  #[test]
+  fn summary_defaults_to_today() {
+    assert_eq!(parse_line("summary").unwrap().command, Command::Summary { period: "day".to_string() });
+  }
+
+  #[test]
+  fn rejects_an_unknown_period() {
+    let error = parse_line("summary --period year").unwrap_err();
+    assert!(error.contains("--period must be day, week or month"), "{}", error);
+  }
+
+  #[test]
+  fn rejects_options_for_other_commands() {
+    assert!(parse_line("status --period week").is_err());
+    assert!(parse_line("summary --last 5").is_err());
+    assert!(parse_line("budgets --style tmux").is_err());
+    assert!(parse_line("statusline --format json").is_err());
+  }
+
+  #[test]
+  fn rejects_bad_values_and_unknown_input() {
+    assert!(parse_line("blocks --last many").is_err());
+    assert!(parse_line("status --format xml").is_err());
+    assert!(parse_line("status --profile").is_err());
+    assert!(parse_line("status --colour").is_err());
+    assert!(parse_line("status extra").is_err());
+    assert!(parse_line("report").is_err());
+  }
+
+  #[test]
+  fn bar_takes_a_style_and_follow() {
+    let command = parse_line("bar --style polybar --follow --no-color").unwrap().command;
+    assert_eq!(command, Command::Bar { style: BarStyle::Polybar, follow: true, color: false });
+  }
+}
@@ -0,0 +1,290 @@
+use std::io::Write;
+use std::time::Duration;
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use crate::application::{alerts::{AlertEngine, PeriodCosts}, status_template::format_duration};
+use crate::domain::entities::{AlertSeverity, UsageSnapshot, UsageStats};
+use crate::infrastructure::{api_schema::{StreamEvent, StreamMessage}, api_token};
+use super::{
+  args::BarStyle,
+  commands::Context,
+  output::{self, group_thousands},
+  statusline::{block_end, latest_snapshot, Latest},
+};
+
+/// Pause before reconnecting to the app, and between reads of the snapshot
+/// file when the local API is off.
+const RETRY: Duration = Duration::from_secs(5);
+/// The app sends a keep-alive every 15 seconds; longer silence means it is gone.
+const STREAM_IDLE: Duration = Duration::from_secs(45);
+/// i3blocks marks a block urgent when its command exits with 33.
+const I3BLOCKS_URGENT: i32 = 33;
+
+/// The webhook colours for warning and critical alerts.
+const WARNING_COLOR: &str = "#ffa726";
+const CRITICAL_COLOR: &str = "#ff4757";
+const IDLE_COLOR: &str = "#888888";
+
+/// What a bar shows, before it is written in that bar's syntax.
+struct Module {
+  text: String,
+  short_text: String,
+  tooltip: String,
+  /// `unavailable`, `idle`, or the severity of the worst alert the usage raises.
+  class: String,
+  /// `None` until the app has polled.
+  severity: Option<AlertSeverity>,
+  idle: bool,
+  percentage: Option<f32>,
+}
+
+#[derive(Serialize)]
+struct WaybarOutput<'a> {
+  text: &'a str,
+  tooltip: &'a str,
+  class: &'a str,
+  alt: &'a str,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  percentage: Option<u32>,
+}
+
+/// The i3blocks JSON form, for `interval=persist` with `format=json`.
+#[derive(Serialize)]
+struct I3blocksOutput<'a> {
+  full_text: &'a str,
+  short_text: &'a str,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  color: Option<&'a str>,
+  urgent: bool,
+}
+
+/// One update in the bar's format, with the exit code i3blocks reads to
+/// mark the block urgent.
+pub async fn bar(context: &Context, style: BarStyle, color: bool) -> (String, i32) {
+  let latest = latest_snapshot(context).await;
+  let module = module(context, latest.as_ref(), Utc::now());
+  let code = if style == BarStyle::I3blocks && module.severity == Some(AlertSeverity::Critical) { I3BLOCKS_URGENT } else { 0 };
+  (render(style, &module, color, false), code)
+}
+
+/// Prints a line whenever the app polls, until the bar closes the pipe.
+/// Updates are pushed over the local API's event stream when it is enabled;
+/// otherwise the snapshot file is re-read every few seconds.
+pub async fn follow(context: &Context, style: BarStyle, color: bool) -> i32 {
+  let mut follower = Follower { context, style, color, last: String::new() };
+  if !follower.show(latest_snapshot(context).await.as_ref()) {
+    return 0;
+  }
+  loop {
+    if context.settings.api.enabled {
+      match stream(&mut follower).await {
+        Ok(()) => return 0,
+        // Bars log stderr without displaying it.
+        Err(e) => eprintln!("⚠️ {}", e),
+      }
+    }
+    tokio::time::sleep(RETRY).await;
+    if !follower.show(latest_snapshot(context).await.as_ref()) {
+      return 0;
+    }
+  }
+}
+
+struct Follower<'a> {
+  context: &'a Context,
+  style: BarStyle,
+  color: bool,
+  last: String,
+}
+
+impl Follower<'_> {
+  /// Prints the module unless it is unchanged; false once stdout is closed.
+  fn show(&mut self, latest: Option<&Latest>) -> bool {
+    let line = render(self.style, &module(self.context, latest, Utc::now()), self.color, true);
+    if line == self.last {
+      return true;
+    }
+    let mut stdout = std::io::stdout().lock();
+    let written = writeln!(stdout, "{}", line).and_then(|_| stdout.flush()).is_ok();
+    self.last = line;
+    written
+  }
+}
+
+/// Follows `/v1/stream`; returns once stdout is closed, or an error when the
+/// app cannot be reached or goes away.
+async fn stream(follower: &mut Follower<'_>) -> Result<(), String> {
+  let settings = &follower.context.settings;
+  let token = api_token::resolve(settings.api.token.as_deref())?;
+  let client = reqwest::Client::builder()
+    .connect_timeout(Duration::from_secs(2))
+    .build()
+    .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+  let mut response = client
+    .get(format!("http://127.0.0.1:{}/v1/stream?topics=usage", settings.api.port))
+    .bearer_auth(token)
+    .send()
+    .await
+    .and_then(|response| response.error_for_status())
+    .map_err(|e| format!("Local API unavailable: {}", e))?;
+
+  let mut buffer = Vec::new();
+  loop {
+    let chunk = tokio::time::timeout(STREAM_IDLE, response.chunk())
+      .await
+      .map_err(|_| "The event stream went quiet".to_string())?
+      .map_err(|e| format!("The event stream broke: {}", e))?
+      .ok_or("The app closed the event stream")?;
+    buffer.extend_from_slice(&chunk);
+    while let Some(end) = buffer.windows(2).position(|pair| pair == b"\n\n") {
+      let frame: Vec<u8> = buffer.drain(..end + 2).collect();
+      let Some(snapshot) = usage_event(&String::from_utf8_lossy(&frame)) else { continue };
+      if snapshot.profile != settings.active_profile {
+        continue;
+      }
+      if !follower.show(Some(&Latest { snapshot, stale: false })) {
+        return Ok(());
+      }
+    }
+  }
+}
+
+/// The snapshot in a `usage` event; keep-alives and other topics give `None`.
+fn usage_event(frame: &str) -> Option<UsageSnapshot> {
+  let data: Vec<&str> = frame.lines()
+    .filter_map(|line| line.strip_prefix("data:"))
+    .map(str::trim_start)
+    .collect();
+  if data.is_empty() {
+    return None;
+  }
+  match serde_json::from_str::<StreamMessage>(&data.join("\n")).ok()?.event {
+    StreamEvent::Usage(snapshot) => Some(snapshot),
+    _ => None,
+  }
+}
+
+fn module(context: &Context, latest: Option<&Latest>, now: DateTime<Utc>) -> Module {
+  let Some(latest) = latest else {
+    return Module {
+      text: "n/a".to_string(),
+      short_text: "n/a".to_string(),
+      tooltip: "No usage yet; is the app running?".to_string(),
+      class: "unavailable".to_string(),
+      severity: None,
+      idle: false,
+      percentage: None,
+    };
+  };
+  let profile = context.settings.profile();
+  let currency = &profile.currency;
+  let stats = &latest.snapshot.stats;
+  let mut tooltip = Vec::new();
+
+  let end = block_end(stats, now);
+  let (text, short_text) = match end {
+    Some(end) => {
+      let minutes = ((end - now).num_seconds().max(0) as u32).div_ceil(60);
+      let usage = match stats.percent_of_block_limit {
+        Some(percent) => format!("{:.0}%", percent),
+        None => currency.format(stats.session_cost),
+      };
+      let limit = stats.percent_of_block_limit.map(|percent| format!(", {:.1}% of limit", percent)).unwrap_or_default();
+      tooltip.push(format!("Block: {} tokens, {}{}", group_thousands(stats.current_tokens as u64), currency.format(stats.session_cost), limit));
+      tooltip.push(format!("Resets in {}", format_duration(minutes)));
+      if let Some(burn_rate) = stats.burn_rate {
+        tooltip.push(format!("Burn rate: {} tokens/min", group_thousands(burn_rate as u64)));
+      }
+      (format!("{} · {}", usage, format_duration(minutes)), usage)
+    }
+    None => {
+      tooltip.push("No active block".to_string());
+      ("idle".to_string(), "idle".to_string())
+    }
+  };
+  let severity = severity(context, stats, &mut tooltip);
+  // Between blocks the module is idle unless a daily budget still warns.
+  let idle = end.is_none() && severity == AlertSeverity::Info;
+
+  tooltip.push(format!("Today: {} tokens, {}", group_thousands(stats.daily_tokens as u64), currency.format(stats.cost)));
+  if let Some(percent) = stats.percent_of_weekly_limit {
+    tooltip.push(format!("Week: {:.1}% of limit", percent));
+  }
+  let updated_at = DateTime::parse_from_rfc3339(&latest.snapshot.updated_at)
+    .map(|time| time.with_timezone(&Local).format("%H:%M").to_string())
+    .unwrap_or_else(|_| latest.snapshot.updated_at.clone());
+  tooltip.push(format!("Updated {}{}", updated_at, if latest.stale { " (stale)" } else { "" }));
+
+  Module {
+    text,
+    short_text,
+    tooltip: tooltip.join("\n"),
+    class: if idle { "idle".to_string() } else { output::label(&severity) },
+    severity: Some(severity),
+    idle,
+    percentage: stats.percent_of_block_limit.filter(|_| end.is_some()),
+  }
+}
+
+/// The worst of the plan-limit thresholds and the alerts the app would raise
+/// for these stats; alert titles are added to the tooltip. Week and month
+/// budgets need ccusage and are left out.
+fn severity(context: &Context, stats: &UsageStats, tooltip: &mut Vec<String>) -> AlertSeverity {
+  let thresholds = &context.settings.thresholds;
+  let percent = stats.percent_of_block_limit.into_iter().chain(stats.percent_of_weekly_limit).fold(0.0, f32::max);
+  let mut severity = if percent >= thresholds.critical {
+    AlertSeverity::Critical
+  } else if percent >= thresholds.warning {
+    AlertSeverity::Warning
+  } else {
+    AlertSeverity::Info
+  };
+  // A fresh engine reports every condition that currently holds.
+  let rules = &context.settings.profile().notifications.rules;
+  for alert in AlertEngine::new().evaluate(rules, stats, &PeriodCosts::default()) {
+    tooltip.push(format!("⚠ {}", alert.title));
+    severity = severity.max(alert.severity);
+  }
+  severity
+}
+
+fn render(style: BarStyle, module: &Module, color: bool, follow: bool) -> String {
+  let color = if color { module_color(module) } else { None };
+  match style {
+    // Waybar styles by class, so the colour is left to its CSS.
+    BarStyle::Waybar => serde_json::to_string(&WaybarOutput {
+      text: &module.text,
+      tooltip: &module.tooltip,
+      class: &module.class,
+      alt: &module.class,
+      percentage: module.percentage.map(|percent| percent.round().clamp(0.0, 100.0) as u32),
+    })
+    .unwrap_or_default(),
+    BarStyle::I3blocks if follow => serde_json::to_string(&I3blocksOutput {
+      full_text: &module.text,
+      short_text: &module.short_text,
+      color,
+      urgent: module.severity == Some(AlertSeverity::Critical),
+    })
+    .unwrap_or_default(),
+    // The plain protocol: full text, short text and colour on their own lines.
+    BarStyle::I3blocks => [Some(module.text.as_str()), Some(module.short_text.as_str()), color].into_iter().flatten().collect::<Vec<_>>().join("\n"),
+    BarStyle::Polybar => match color {
+      Some(color) => format!("%{{F{}}}{}%{{F-}}", color, module.text),
+      None => module.text.clone(),
+    },
+    BarStyle::Tmux => match color {
+      Some(color) => format!("#[fg={}]{}#[default]", color, module.text),
+      None => module.text.clone(),
+    },
+  }
+}
+
+fn module_color(module: &Module) -> Option<&'static str> {
+  match module.severity {
+    Some(AlertSeverity::Critical) => Some(CRITICAL_COLOR),
+    Some(AlertSeverity::Warning) => Some(WARNING_COLOR),
+    Some(AlertSeverity::Info) if !module.idle => None,
+    _ => Some(IDLE_COLOR),
+  }
+}
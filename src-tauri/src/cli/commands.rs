@@ -0,0 +1,318 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Local;
+use serde::Serialize;
+use crate::application::{alerts::PeriodCosts, budgets, use_cases};
+use crate::domain::{
+  entities::{UsageSnapshot, UsageStats},
+  repository::UsageRepository,
+  settings::{AppSettings, Profile},
+};
+use crate::infrastructure::{
+  api_schema::Health,
+  api_token,
+  ccusage_repository::CcusageRepository,
+  policy_store::{self, LoadedPolicy},
+  settings_store::SettingsStore,
+};
+use super::{
+  args::Format,
+  output::{self, Cell, Table},
+};
+
+/// Settings, policy and repository resolved the same way the tray app does.
+pub struct Context {
+  pub settings: AppSettings,
+  pub settings_path: Option<PathBuf>,
+  /// Why the settings file was ignored, if it was.
+  pub settings_error: Option<String>,
+  pub policy: LoadedPolicy,
+  pub repo: Arc<CcusageRepository>,
+}
+
+impl Context {
+  /// Loads the user's settings with the organization policy applied. A
+  /// broken settings file falls back to defaults, like the app does, and is
+  /// reported through `settings_error`.
+  pub fn load(profile: Option<&str>) -> Result<Self, String> {
+    let settings_path = SettingsStore::default_path().ok();
+    let (mut settings, settings_error) = match &settings_path {
+      Some(path) => match SettingsStore::new(path.clone()).load() {
+        Ok(settings) => (settings, None),
+        Err(e) => (AppSettings::default(), Some(e)),
+      },
+      None => (AppSettings::default(), Some("Could not determine the platform config directory".to_string())),
+    };
+    if let Some(name) = profile {
+      if !settings.profiles.contains_key(name) {
+        return Err(format!("Profile '{}' does not exist", name));
+      }
+      settings.active_profile = name.to_string();
+    }
+    // `--profile` is a user choice like any other, so the policy is merged
+    // over it rather than the other way round.
+    let policy = policy_store::load_policy();
+    if let Some(policy) = &policy.policy {
+      settings = policy.apply(&settings);
+    }
+    let repo = Arc::new(CcusageRepository::with_data_roots(settings.profile().expanded_data_roots()));
+    Ok(Self { settings, settings_path, settings_error, policy, repo })
+  }
+
+  fn profile(&self) -> &Profile {
+    self.settings.profile()
+  }
+
+  fn repo(&self) -> Arc<dyn UsageRepository + Send + Sync> {
+    self.repo.clone()
+  }
+
+  /// Current stats against the plan limits, detecting the block limit from
+  /// history only when the plan asks for it and sets none explicitly.
+  async fn stats(&self) -> Result<UsageStats, String> {
+    let plan = &self.profile().plan;
+    let detected = if plan.auto_detect_block_limit && plan.block_token_limit.is_none() {
+      use_cases::detect_block_limit(self.repo()).await.ok().flatten()
+    } else {
+      None
+    };
+    use_cases::get_claude_usage(self.repo(), &plan.limits(detected.as_ref())).await
+  }
+
+  fn render<T: Serialize>(&self, format: Format, data: &T, table: &Table) -> String {
+    output::render(format, data, table, &self.profile().currency)
+  }
+}
+
+pub async fn status(context: &Context, format: Format) -> Result<String, String> {
+  let stats = context.stats().await?;
+  let rows = vec![
+    ("profile", Cell::Text(context.settings.active_profile.clone())),
+    ("active_block", Cell::Text(if stats.active_session { "yes" } else { "no" }.to_string())),
+    ("block_tokens", Cell::Tokens(stats.current_tokens as u64)),
+    ("block_cost", Cell::Usd(stats.session_cost as f64)),
+    ("block_limit", Cell::Percent(stats.percent_of_block_limit)),
+    ("burn_rate_per_minute", Cell::Number(stats.burn_rate.map(|rate| rate as f64))),
+    ("block_resets_in", Cell::Minutes(stats.block_minutes_remaining.filter(|_| stats.active_session))),
+    ("block_end", Cell::Time(stats.block_end_time.clone().filter(|_| stats.active_session))),
+    ("today_tokens", Cell::Tokens(stats.daily_tokens as u64)),
+    ("today_cost", Cell::Usd(stats.cost as f64)),
+    ("week_tokens", Cell::Tokens(stats.weekly_tokens)),
+    ("weekly_limit", Cell::Percent(stats.percent_of_weekly_limit)),
+    ("model", Cell::Text(stats.model.clone())),
+  ];
+  let table = Table {
+    headers: vec!["field", "value"],
+    rows: rows.into_iter().map(|(field, value)| vec![Cell::Text(field.to_string()), value]).collect(),
+  };
+  let snapshot = UsageSnapshot {
+    profile: context.settings.active_profile.clone(),
+    updated_at: Local::now().to_rfc3339(),
+    stats,
+  };
+  Ok(context.render(format, &snapshot, &table))
+}
+
+pub async fn summary(context: &Context, format: Format, period: &str) -> Result<String, String> {
+  let summary = use_cases::get_usage_summary(context.repo(), period.to_string()).await?;
+  let table = Table {
+    headers: vec!["period", "start_date", "end_date", "days", "total_tokens", "total_cost", "avg_tokens_per_day", "avg_cost_per_day"],
+    rows: vec![vec![
+      Cell::Text(summary.period.clone()),
+      Cell::Text(summary.start_date.clone()),
+      Cell::Text(summary.end_date.clone()),
+      Cell::Number(Some(summary.days as f64)),
+      Cell::Tokens(summary.total_tokens),
+      Cell::Usd(summary.total_cost as f64),
+      Cell::Number(Some(summary.avg_tokens_per_day as f64)),
+      Cell::Usd(summary.avg_cost_per_day as f64),
+    ]],
+  };
+  Ok(context.render(format, &summary, &table))
+}
+
+pub async fn blocks(context: &Context, format: Format, last: usize) -> Result<String, String> {
+  let blocks: Vec<_> = use_cases::get_blocks(context.repo()).await?
+    .into_iter()
+    .filter(|block| !block.is_gap)
+    .collect();
+  let blocks = blocks[blocks.len().saturating_sub(last)..].to_vec();
+  let table = Table {
+    headers: vec!["start_time", "end_time", "status", "entries", "total_tokens", "cost", "models"],
+    rows: blocks.iter()
+      .map(|block| vec![
+        Cell::Time(Some(block.start_time.clone())),
+        Cell::Time(Some(block.actual_end_time.clone().unwrap_or_else(|| block.end_time.clone()))),
+        Cell::Text(if block.is_active { "active" } else { "done" }.to_string()),
+        Cell::Number(Some(block.entries as f64)),
+        Cell::Tokens(block.total_tokens as u64),
+        Cell::Usd(block.cost_usd as f64),
+        Cell::Text(block.models.join(" ")),
+      ])
+      .collect(),
+  };
+  Ok(context.render(format, &blocks, &table))
+}
+
+pub async fn budgets(context: &Context, format: Format) -> Result<String, String> {
+  let rules = &context.profile().notifications.rules;
+  let stats = context.stats().await?;
+  let mut period_costs = PeriodCosts::default();
+  if rules.weekly_budget_usd.is_some() {
+    period_costs.weekly_cost = period_cost(context, "week").await;
+  }
+  if rules.monthly_budget_usd.is_some() {
+    period_costs.monthly_cost = period_cost(context, "month").await;
+  }
+  let statuses = budgets::budget_statuses(rules, &stats, &period_costs);
+  if statuses.is_empty() && format == Format::Table {
+    eprintln!("No budgets are set for profile '{}'", context.settings.active_profile);
+  }
+  let table = Table {
+    headers: vec!["period", "limit", "spent", "remaining", "percent", "state"],
+    rows: statuses.iter()
+      .map(|status| vec![
+        Cell::Text(status.period.clone()),
+        Cell::Usd(status.limit_usd as f64),
+        Cell::Usd(status.spent_usd as f64),
+        Cell::Usd(status.remaining_usd as f64),
+        Cell::Percent(Some(status.percent)),
+        Cell::Text(output::label(&status.state)),
+      ])
+      .collect(),
+  };
+  Ok(context.render(format, &statuses, &table))
+}
+
+/// A period that fails to load is left out with a warning, so the other
+/// budgets are still reported.
+async fn period_cost(context: &Context, period: &str) -> Option<f32> {
+  match use_cases::get_usage_summary(context.repo(), period.to_string()).await {
+    Ok(summary) => Some(summary.total_cost),
+    Err(e) => {
+      eprintln!("Skipping the {} budget: {}", period, e);
+      None
+    }
+  }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckState {
+  Ok,
+  Warn,
+  Fail,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Check {
+  pub name: String,
+  pub state: CheckState,
+  pub detail: String,
+}
+
+impl Check {
+  fn new(name: &str, state: CheckState, detail: impl Into<String>) -> Self {
+    Self { name: name.to_string(), state, detail: detail.into() }
+  }
+}
+
+/// Runs every check; the result is false when any of them failed.
+pub async fn doctor(context: &Context, format: Format) -> (String, bool) {
+  let mut checks = Vec::new();
+
+  let settings_path = context.settings_path.as_ref().map(|path| path.display().to_string()).unwrap_or_default();
+  checks.push(match (&context.settings_error, context.settings_path.as_ref().is_some_and(|path| path.exists())) {
+    (Some(e), _) => Check::new("settings", CheckState::Fail, format!("{} (using defaults)", e)),
+    (None, true) => Check::new("settings", CheckState::Ok, settings_path),
+    (None, false) => Check::new("settings", CheckState::Ok, format!("{} not created yet, using defaults", settings_path)),
+  });
+
+  checks.push(match (&context.policy.source, &context.policy.error) {
+    (Some(_), Some(e)) => Check::new("policy", CheckState::Fail, e.clone()),
+    (Some(source), None) => Check::new("policy", CheckState::Ok, source.display().to_string()),
+    _ => Check::new("policy", CheckState::Ok, "none"),
+  });
+
+  let roots = context.profile().expanded_data_roots();
+  let roots = if roots.is_empty() {
+    "ccusage default data directories".to_string()
+  } else {
+    roots.iter().map(|root| root.display().to_string()).collect::<Vec<_>>().join(", ")
+  };
+  checks.push(Check::new("profile", CheckState::Ok, format!("{} ({})", context.settings.active_profile, roots)));
+
+  match context.repo.find_node_and_ccusage_paths() {
+    Ok((node, ccusage)) => {
+      for (name, path, bare) in [("node", node, "node"), ("ccusage", ccusage, "ccusage")] {
+        checks.push(if path == bare {
+          Check::new(name, CheckState::Warn, format!("not in a known location, relying on PATH for '{}'", bare))
+        } else {
+          Check::new(name, CheckState::Ok, path)
+        });
+      }
+    }
+    Err(e) => checks.push(Check::new("ccusage", CheckState::Fail, e)),
+  }
+
+  checks.push(match context.stats().await {
+    Ok(stats) => Check::new(
+      "usage",
+      CheckState::Ok,
+      format!("today {} tokens, {}", stats.daily_tokens, context.profile().currency.format(stats.cost)),
+    ),
+    Err(e) => Check::new("usage", CheckState::Fail, e.lines().next().unwrap_or_default()),
+  });
+
+  checks.push(local_api_check(context).await);
+
+  if let Some(path) = context.settings.metrics.expanded_textfile() {
+    let directory = path.parent().map(|parent| parent.is_dir()).unwrap_or(false);
+    checks.push(if directory {
+      Check::new("metrics_textfile", CheckState::Ok, path.display().to_string())
+    } else {
+      Check::new("metrics_textfile", CheckState::Warn, format!("{} is in a directory that does not exist yet", path.display()))
+    });
+  }
+
+  let healthy = checks.iter().all(|check| check.state != CheckState::Fail);
+  let table = Table {
+    headers: vec!["check", "state", "detail"],
+    rows: checks.iter()
+      .map(|check| vec![
+        Cell::Text(check.name.clone()),
+        Cell::Text(output::label(&check.state)),
+        Cell::Text(check.detail.clone()),
+      ])
+      .collect(),
+  };
+  (context.render(format, &checks, &table), healthy)
+}
+
+async fn local_api_check(context: &Context) -> Check {
+  let api = &context.settings.api;
+  if !api.enabled {
+    return Check::new("local_api", CheckState::Ok, "disabled");
+  }
+  if api.token.is_none() && api_token::read().is_err() {
+    return Check::new("local_api", CheckState::Warn, "enabled, but no token file yet; start the app once to generate it");
+  }
+  let url = format!("http://127.0.0.1:{}/v1/health", api.port);
+  let client = reqwest::Client::builder().timeout(Duration::from_secs(2)).build();
+  let health = match client {
+    Ok(client) => match client.get(&url).send().await {
+      Ok(response) => response.json::<Health>().await.map_err(|e| e.to_string()),
+      Err(e) => Err(e.to_string()),
+    },
+    Err(e) => Err(e.to_string()),
+  };
+  match health {
+    Ok(health) => Check::new(
+      "local_api",
+      CheckState::Ok,
+      format!("listening on port {} (app {}, schema {})", api.port, health.app_version, health.schema_version),
+    ),
+    Err(e) => Check::new("local_api", CheckState::Warn, format!("enabled but not reachable on port {}; is the app running? ({})", api.port, e)),
+  }
+}
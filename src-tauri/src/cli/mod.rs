@@ -0,0 +1,99 @@
+mod args;
+mod bar;
+mod commands;
+mod output;
+mod statusline;
+
+use std::io::Write;
+use args::Command;
+use commands::Context;
+use crate::infrastructure::ccusage_repository;
+
+/// Entry point of the headless `claude-token-monitor` binary; returns the
+/// process exit code: 0 on success, 1 when a command or check failed and 2
+/// for invalid arguments.
+pub fn run(args: Vec<String>) -> i32 {
+  let invocation = match args::parse(&args) {
+    Ok(invocation) => invocation,
+    Err(e) => {
+      eprintln!("❌ {}\n\n{}", e, args::USAGE);
+      return 2;
+    }
+  };
+  match invocation.command {
+    Command::Help => {
+      print(args::USAGE);
+      return 0;
+    }
+    Command::Version => {
+      print(&format!("claude-token-monitor {}", env!("CARGO_PKG_VERSION")));
+      return 0;
+    }
+    _ => {}
+  }
+  ccusage_repository::set_discovery_logging(invocation.verbose);
+
+  let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+    Ok(runtime) => runtime,
+    Err(e) => {
+      eprintln!("❌ Failed to start async runtime: {}", e);
+      return 1;
+    }
+  };
+  runtime.block_on(async {
+    let context = match Context::load(invocation.profile.as_deref()) {
+      Ok(context) => context,
+      Err(e) => {
+        eprintln!("❌ {}", e);
+        return 1;
+      }
+    };
+    // Only `doctor` runs with a broken policy, so it can report it.
+    if let (Some(e), false) = (&context.policy.error, matches!(invocation.command, Command::Doctor)) {
+      eprintln!("❌ {}", e);
+      return 1;
+    }
+    if let (Some(e), false) = (&context.settings_error, matches!(invocation.command, Command::Doctor | Command::Statusline { .. } | Command::Bar { .. })) {
+      eprintln!("⚠️ Ignoring settings: {}", e);
+    }
+
+    let format = invocation.format;
+    let result = match &invocation.command {
+      Command::Status => commands::status(&context, format).await,
+      Command::Summary { period } => commands::summary(&context, format, period).await,
+      Command::Blocks { last } => commands::blocks(&context, format, *last).await,
+      Command::Budgets => commands::budgets(&context, format).await,
+      Command::Doctor => {
+        let (report, healthy) = commands::doctor(&context, format).await;
+        print(&report);
+        return if healthy { 0 } else { 1 };
+      }
+      Command::Bar { style, follow: true, color } => return bar::follow(&context, *style, *color).await,
+      Command::Bar { style, follow: false, color } => {
+        let (line, code) = bar::bar(&context, *style, *color).await;
+        print(&line);
+        return code;
+      }
+      Command::Statusline { color } => {
+        print(&statusline::statusline(&context, *color).await);
+        return 0;
+      }
+      Command::Help | Command::Version => unreachable!("handled before loading settings"),
+    };
+    match result {
+      Ok(output) => {
+        print(&output);
+        0
+      }
+      Err(e) => {
+        eprintln!("{}", e);
+        1
+      }
+    }
+  })
+}
+
+/// Like `println!`, but a closed pipe (e.g. `| head`) is not a panic.
+fn print(text: &str) {
+  let _ = writeln!(std::io::stdout().lock(), "{}", text);
+}
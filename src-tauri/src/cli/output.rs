@@ -0,0 +1,132 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use crate::application::status_template::format_duration;
+use crate::domain::settings::CurrencyConfig;
+use crate::infrastructure::api_schema::Envelope;
+use super::args::Format;
+
+/// A table value; tables show it for people, CSV keeps it machine-readable.
+pub enum Cell {
+  Text(String),
+  Tokens(u64),
+  /// Tables convert to the profile currency; CSV stays in US dollars.
+  Usd(f64),
+  Percent(Option<f32>),
+  Number(Option<f64>),
+  Minutes(Option<u32>),
+  /// RFC 3339; tables show local time.
+  Time(Option<String>),
+}
+
+pub struct Table {
+  /// snake_case column names, used as-is for the CSV header.
+  pub headers: Vec<&'static str>,
+  pub rows: Vec<Vec<Cell>>,
+}
+
+/// Renders `data` as JSON in the local API envelope, or `table` as text or CSV.
+pub fn render<T: Serialize>(format: Format, data: &T, table: &Table, currency: &CurrencyConfig) -> String {
+  match format {
+    Format::Json => serde_json::to_string_pretty(&Envelope::new(data)).unwrap_or_default(),
+    Format::Csv => render_csv(table),
+    Format::Table => render_table(table, currency),
+  }
+}
+
+/// The serialized name of a unit enum such as `BudgetState::Warning`.
+pub fn label<T: Serialize>(value: &T) -> String {
+  serde_json::to_value(value).ok().and_then(|value| value.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+fn render_table(table: &Table, currency: &CurrencyConfig) -> String {
+  let header: Vec<String> = table.headers.iter().map(|name| name.replace('_', " ").to_uppercase()).collect();
+  let rows: Vec<Vec<String>> = table.rows.iter()
+    .map(|row| row.iter().map(|cell| display(cell, currency)).collect())
+    .collect();
+  let widths: Vec<usize> = (0..header.len())
+    .map(|column| {
+      rows.iter()
+        .filter_map(|row| row.get(column))
+        .map(|text| text.chars().count())
+        .chain([header[column].chars().count()])
+        .max()
+        .unwrap_or(0)
+    })
+    .collect();
+  // Numbers line up on the right, unless a column mixes them with text.
+  let right: Vec<bool> = (0..header.len())
+    .map(|column| !table.rows.is_empty() && table.rows.iter().all(|row| row.get(column).is_some_and(is_numeric)))
+    .collect();
+
+  let mut lines = vec![pad_row(&header, &widths, &[])];
+  lines.extend(rows.iter().map(|row| pad_row(row, &widths, &right)));
+  lines.join("\n")
+}
+
+fn pad_row(cells: &[String], widths: &[usize], right: &[bool]) -> String {
+  cells.iter().zip(widths).enumerate()
+    .map(|(column, (text, width))| {
+      if right.get(column) == Some(&true) {
+        format!("{:>width$}", text, width = width)
+      } else {
+        format!("{:<width$}", text, width = width)
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("  ")
+    .trim_end()
+    .to_string()
+}
+
+fn display(cell: &Cell, currency: &CurrencyConfig) -> String {
+  match cell {
+    Cell::Text(text) => text.clone(),
+    Cell::Tokens(tokens) => group_thousands(*tokens),
+    Cell::Usd(usd) => currency.format(*usd as f32),
+    Cell::Percent(Some(percent)) => format!("{:.1}%", percent),
+    Cell::Number(Some(number)) => format!("{:.0}", number),
+    Cell::Minutes(Some(minutes)) => format_duration(*minutes),
+    Cell::Time(Some(time)) => DateTime::parse_from_rfc3339(time)
+      .map(|time| time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+      .unwrap_or_else(|_| time.clone()),
+    Cell::Percent(None) | Cell::Number(None) | Cell::Minutes(None) | Cell::Time(None) => "-".to_string(),
+  }
+}
+
+fn is_numeric(cell: &Cell) -> bool {
+  matches!(cell, Cell::Tokens(_) | Cell::Usd(_) | Cell::Percent(_) | Cell::Number(_) | Cell::Minutes(_))
+}
+
+fn render_csv(table: &Table) -> String {
+  let mut lines = vec![table.headers.join(",")];
+  for row in &table.rows {
+    let fields: Vec<String> = row.iter().map(|cell| csv_field(&raw(cell))).collect();
+    lines.push(fields.join(","));
+  }
+  lines.join("\n")
+}
+
+fn raw(cell: &Cell) -> String {
+  match cell {
+    Cell::Text(text) => text.clone(),
+    Cell::Tokens(tokens) => tokens.to_string(),
+    Cell::Usd(usd) => format!("{:.4}", usd),
+    Cell::Percent(percent) => percent.map(|percent| format!("{:.2}", percent)).unwrap_or_default(),
+    Cell::Number(number) => number.map(|number| format!("{:.2}", number)).unwrap_or_default(),
+    Cell::Minutes(minutes) => minutes.map(|minutes| minutes.to_string()).unwrap_or_default(),
+    Cell::Time(time) => time.clone().unwrap_or_default(),
+  }
+}
+
+fn csv_field(value: &str) -> String {
+  if value.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", value.replace('"', "\"\""))
+  } else {
+    value.to_string()
+  }
+}
+
+pub fn group_thousands(number: u64) -> String {
+  let digits: Vec<char> = number.to_string().chars().collect();
+  digits.rchunks(3).rev().map(|group| group.iter().collect::<String>()).collect::<Vec<_>>().join(",")
+}
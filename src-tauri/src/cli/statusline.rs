@@ -0,0 +1,192 @@
+use std::fs;
+use std::io::{IsTerminal, Read};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use crate::application::status_template::format_duration;
+use crate::domain::entities::{UsageSnapshot, UsageStats};
+use crate::domain::settings::{CurrencyConfig, WarningThresholds};
+use crate::infrastructure::{api_schema::Envelope, api_token, snapshot_cache};
+use super::commands::Context;
+
+/// The status line runs on every prompt, so the local API gets very little time.
+const API_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// The parts of Claude Code's status line JSON this command reads; anything
+/// missing is simply left out of the line.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct SessionInput {
+  transcript_path: Option<String>,
+  model: ModelInput,
+  cost: CostInput,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ModelInput {
+  display_name: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct CostInput {
+  total_cost_usd: Option<f64>,
+}
+
+/// Where the usage came from; a stale snapshot is still shown, dimmed.
+pub struct Latest {
+  pub snapshot: UsageSnapshot,
+  pub stale: bool,
+}
+
+/// One line for Claude Code's `statusLine`: model, session cost, block usage
+/// and time to reset. Never runs ccusage, so it stays fast on every prompt.
+pub async fn statusline(context: &Context, color: bool) -> String {
+  let session = read_session();
+  let paint = Paint { color };
+  let currency = &context.settings.profile().currency;
+  let mut parts = Vec::new();
+
+  if let Some(model) = session.model.display_name.filter(|name| !name.is_empty()) {
+    parts.push(paint.bold(&model));
+  }
+  if let Some(cost) = session.cost.total_cost_usd {
+    parts.push(format!("{} session", currency.format(cost as f32)));
+  }
+
+  let now = Utc::now();
+  match latest_snapshot(context).await {
+    Some(latest) => {
+      let stats = &latest.snapshot.stats;
+      match block_end(stats, now) {
+        Some(end) if in_block(session.transcript_path.as_deref(), stats, end) => {
+          parts.push(block_usage(stats, &context.settings.thresholds, currency, &paint));
+          let minutes = ((end - now).num_seconds().max(0) as u32).div_ceil(60);
+          parts.push(format!("↻ {}", format_duration(minutes)));
+        }
+        _ => parts.push(paint.dim("no active block")),
+      }
+      if latest.stale {
+        parts.push(paint.dim("stale"));
+      }
+    }
+    None => parts.push(paint.dim("usage unavailable; is the app running?")),
+  }
+  parts.join(" · ")
+}
+
+/// The app's latest poll for the active profile: the snapshot file if it is
+/// recent, else the local API, else an old snapshot marked stale.
+pub async fn latest_snapshot(context: &Context) -> Option<Latest> {
+  let settings = &context.settings;
+  let cached = snapshot_cache::read().ok().filter(|snapshot| snapshot.profile == settings.active_profile);
+  // A few missed polls are fine; beyond that the app is probably not running.
+  let max_age = chrono::Duration::seconds((settings.poll_interval_secs * 3).max(300) as i64);
+  let fresh = |snapshot: &UsageSnapshot| {
+    DateTime::parse_from_rfc3339(&snapshot.updated_at)
+      .is_ok_and(|updated_at| Utc::now() - updated_at.with_timezone(&Utc) <= max_age)
+  };
+
+  if let Some(snapshot) = cached.clone().filter(|snapshot| fresh(snapshot)) {
+    return Some(Latest { snapshot, stale: false });
+  }
+  if settings.api.enabled {
+    if let Some(snapshot) = fetch_snapshot(context).await.filter(|snapshot| snapshot.profile == settings.active_profile) {
+      let stale = !fresh(&snapshot);
+      return Some(Latest { snapshot, stale });
+    }
+  }
+  cached.map(|snapshot| Latest { snapshot, stale: true })
+}
+
+async fn fetch_snapshot(context: &Context) -> Option<UsageSnapshot> {
+  let api = &context.settings.api;
+  let token = api_token::resolve(api.token.as_deref()).ok()?;
+  let client = reqwest::Client::builder().timeout(API_TIMEOUT).build().ok()?;
+  let response = client
+    .get(format!("http://127.0.0.1:{}/v1/usage", api.port))
+    .bearer_auth(token)
+    .send()
+    .await
+    .ok()?
+    .error_for_status()
+    .ok()?;
+  response.json::<Envelope<UsageSnapshot>>().await.ok().map(|envelope| envelope.data)
+}
+
+/// Claude Code pipes its session JSON; a terminal on stdin means a person
+/// is trying the command out, so there is nothing to wait for.
+fn read_session() -> SessionInput {
+  let stdin = std::io::stdin();
+  if stdin.is_terminal() {
+    return SessionInput::default();
+  }
+  let mut input = String::new();
+  if stdin.lock().read_to_string(&mut input).is_err() {
+    return SessionInput::default();
+  }
+  serde_json::from_str(&input).unwrap_or_default()
+}
+
+pub fn block_end(stats: &UsageStats, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+  if !stats.active_session {
+    return None;
+  }
+  let end = DateTime::parse_from_rfc3339(stats.block_end_time.as_deref()?).ok()?.with_timezone(&Utc);
+  (end > now).then_some(end)
+}
+
+/// A session belongs to the active block when its transcript was written
+/// during it; without a transcript the block is assumed to be the session's.
+fn in_block(transcript_path: Option<&str>, stats: &UsageStats, end: DateTime<Utc>) -> bool {
+  let Some(modified) = transcript_path.and_then(|path| fs::metadata(path).ok()?.modified().ok()) else {
+    return true;
+  };
+  let modified = DateTime::<Utc>::from(modified);
+  let start = stats.block_start_time.as_deref()
+    .and_then(|start| DateTime::parse_from_rfc3339(start).ok())
+    .map(|start| start.with_timezone(&Utc));
+  start.is_none_or(|start| modified >= start) && modified <= end
+}
+
+/// The block's share of the plan limit, coloured by the warning thresholds,
+/// or its cost when the plan has no limit.
+fn block_usage(stats: &UsageStats, thresholds: &WarningThresholds, currency: &CurrencyConfig, paint: &Paint) -> String {
+  let Some(percent) = stats.percent_of_block_limit else {
+    return format!("{} block", currency.format(stats.session_cost));
+  };
+  let text = format!("{:.0}% block", percent);
+  if percent >= thresholds.danger {
+    paint.code("1;31", &text)
+  } else if percent >= thresholds.critical {
+    paint.code("31", &text)
+  } else if percent >= thresholds.warning {
+    paint.code("33", &text)
+  } else {
+    paint.code("32", &text)
+  }
+}
+
+/// ANSI styling that can be switched off with `--no-color` or `NO_COLOR`.
+struct Paint {
+  color: bool,
+}
+
+impl Paint {
+  fn code(&self, code: &str, text: &str) -> String {
+    if self.color {
+      format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+      text.to_string()
+    }
+  }
+
+  fn bold(&self, text: &str) -> String {
+    self.code("1", text)
+  }
+
+  fn dim(&self, text: &str) -> String {
+    self.code("2", text)
+  }
+}
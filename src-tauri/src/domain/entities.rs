@@ -1,14 +1,31 @@
+use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct UsageStats {
   pub active_session: bool,
   pub current_tokens: u32,
   pub daily_tokens: u32,
+  /// A heavy week passes `u32::MAX` once cache reads are counted.
+  pub weekly_tokens: u64,
   pub cost: f32,
   pub model: String,
   pub session_cost: f32,
   pub burn_rate: Option<f32>,
+  pub block_start_time: Option<String>,
+  pub block_end_time: Option<String>,
+  pub block_minutes_remaining: Option<u32>,
+  pub percent_of_block_limit: Option<f32>,
+  pub percent_of_weekly_limit: Option<f32>,
+}
+
+/// The latest polled stats and when they were taken, as shared with other local tools.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UsageSnapshot {
+  pub profile: String,
+  /// RFC 3339 time of the poll that produced `stats`.
+  pub updated_at: String,
+  pub stats: UsageStats,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -17,8 +34,463 @@ pub struct UsagePeriodSummary {
   pub start_date: String,
   pub end_date: String,
   pub days: u32,
-  pub total_tokens: u32,
+  pub total_tokens: u64,
   pub total_cost: f32,
   pub avg_tokens_per_day: f32,
   pub avg_cost_per_day: f32,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UsageBlock {
+  pub id: String,
+  pub start_time: String,
+  pub end_time: String,
+  pub actual_end_time: Option<String>,
+  pub is_active: bool,
+  pub is_gap: bool,
+  pub entries: u32,
+  pub total_tokens: u32,
+  pub cost_usd: f32,
+  pub models: Vec<String>,
+}
+
+/// Token counts split by class, as reported by ccusage.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct TokenBreakdown {
+  pub input_tokens: u64,
+  pub output_tokens: u64,
+  pub cache_creation_tokens: u64,
+  pub cache_read_tokens: u64,
+}
+
+impl TokenBreakdown {
+  pub fn total(&self) -> u64 {
+    self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+  }
+
+  pub fn add(&mut self, other: &TokenBreakdown) {
+    self.input_tokens += other.input_tokens;
+    self.output_tokens += other.output_tokens;
+    self.cache_creation_tokens += other.cache_creation_tokens;
+    self.cache_read_tokens += other.cache_read_tokens;
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModelUsage {
+  pub model: String,
+  pub tokens: TokenBreakdown,
+  pub cost_usd: f64,
+}
+
+/// One day of usage with its per-model split.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DailyUsage {
+  /// Local date, `YYYY-MM-DD`.
+  pub date: String,
+  pub tokens: TokenBreakdown,
+  pub cost_usd: f64,
+  pub models: Vec<ModelUsage>,
+}
+
+/// Usage of one Claude Code session, attributed to its project.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionUsage {
+  pub session_id: String,
+  pub project_path: String,
+  /// Local date of the last entry, `YYYY-MM-DD`.
+  pub last_activity: String,
+  pub tokens: TokenBreakdown,
+  pub cost_usd: f64,
+  pub models: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoryPoint {
+  pub date: String,
+  pub tokens: TokenBreakdown,
+  pub total_tokens: u64,
+  pub cost_usd: f64,
+}
+
+/// Daily series over a period, with days without usage filled in as zero.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UsageHistory {
+  pub period: String,
+  pub start_date: String,
+  pub end_date: String,
+  pub points: Vec<HistoryPoint>,
+  pub total_tokens: u64,
+  pub total_cost_usd: f64,
+  pub peak_date: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BreakdownEntry {
+  /// Model name, or the project's display name.
+  pub name: String,
+  /// Full project path for project breakdowns, the model name otherwise.
+  pub key: String,
+  pub tokens: TokenBreakdown,
+  pub total_tokens: u64,
+  pub cost_usd: f64,
+  /// Percentage of the period's total cost.
+  pub cost_share: f64,
+  /// Days with usage for models, sessions for projects.
+  pub count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UsageBreakdown {
+  pub period: String,
+  pub start_date: String,
+  pub end_date: String,
+  pub total_tokens: u64,
+  pub total_cost_usd: f64,
+  /// Sorted by cost, highest first.
+  pub entries: Vec<BreakdownEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeriodTotals {
+  pub start_date: String,
+  pub end_date: String,
+  pub days: u32,
+  pub tokens: TokenBreakdown,
+  pub total_tokens: u64,
+  pub cost_usd: f64,
+  pub avg_cost_per_day: f64,
+}
+
+/// A period next to the one before it, e.g. this week against last week.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeriodComparison {
+  pub period: String,
+  pub current: PeriodTotals,
+  pub previous: PeriodTotals,
+  /// Percentage change; `None` when the previous period had no usage.
+  pub tokens_change_percent: Option<f64>,
+  pub cost_change_percent: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitConfidence {
+  Low,
+  Medium,
+  High,
+}
+
+/// Block token limit derived from the highest completed block in history.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DetectedBlockLimit {
+  pub block_tokens: u64,
+  pub confidence: LimitConfidence,
+  pub completed_blocks: u32,
+  /// Heavy completed blocks whose usage stopped well before their scheduled
+  /// end, i.e. likely cut off by a rate limit.
+  pub limit_hits: u32,
+  pub max_block_start_time: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Plan {
+  /// A Claude subscription, whose limits Anthropic does not publish. The
+  /// former per-tier names are still read so older settings keep loading.
+  #[serde(alias = "pro", alias = "max5x", alias = "max20x", alias = "custom")]
+  Subscription,
+  /// Pay-as-you-go API usage, which has no block or weekly limits.
+  Api,
+}
+
+/// Subscription plan with optional overrides for its token limits.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PlanConfig {
+  pub plan: Plan,
+  pub block_token_limit: Option<u64>,
+  pub weekly_token_limit: Option<u64>,
+  /// Use the limit detected from history when no explicit block limit is set.
+  pub auto_detect_block_limit: bool,
+}
+
+impl Default for PlanConfig {
+  fn default() -> Self {
+    Self {
+      plan: Plan::Subscription,
+      block_token_limit: None,
+      weekly_token_limit: None,
+      auto_detect_block_limit: true,
+    }
+  }
+}
+
+/// Whether the background monitor is polling and alerts are delivered.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MonitorStatus {
+  pub paused: bool,
+  /// RFC 3339 time until which alert sounds and notifications are muted.
+  pub alerts_snoozed_until: Option<String>,
+}
+
+/// Outcome of registering the global shortcut, for diagnostics.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ShortcutStatus {
+  /// The configured accelerator; `None` when the shortcut is disabled.
+  pub accelerator: Option<String>,
+  pub registered: bool,
+  /// Why registration failed, e.g. another application holds the shortcut.
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetState {
+  Ok,
+  /// At or above the profile's `budget_warning_percent`.
+  Warning,
+  Exceeded,
+}
+
+/// Spend against one configured budget.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BudgetStatus {
+  /// `day`, `block`, `week` or `month`.
+  pub period: String,
+  pub limit_usd: f32,
+  pub spent_usd: f32,
+  pub remaining_usd: f32,
+  pub percent: f32,
+  pub state: BudgetState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PlanLimits {
+  pub block_tokens: Option<u64>,
+  pub weekly_tokens: Option<u64>,
+  /// The block limit is the heaviest block seen so far rather than a known
+  /// limit, so going past it does not mean the limit was hit.
+  #[serde(default)]
+  pub block_detected: bool,
+}
+
+impl PlanConfig {
+  /// Effective limits: an explicit limit wins over the detected block limit.
+  /// A low-confidence detection may rest on a single block, so it is not
+  /// used, and API usage is never limited.
+  pub fn limits(&self, detected: Option<&DetectedBlockLimit>) -> PlanLimits {
+    if self.plan == Plan::Api {
+      return PlanLimits { block_tokens: None, weekly_tokens: None, block_detected: false };
+    }
+    let detected_block = detected
+      .filter(|_| self.auto_detect_block_limit)
+      .filter(|detected| matches!(detected.confidence, LimitConfidence::Medium | LimitConfidence::High))
+      .map(|detected| detected.block_tokens);
+    let block_detected = self.block_token_limit.is_none() && detected_block.is_some();
+    PlanLimits {
+      block_tokens: self.block_token_limit.or(detected_block),
+      weekly_tokens: self.weekly_token_limit,
+      block_detected,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSeverity {
+  Info,
+  Warning,
+  Critical,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+  BudgetWarning,
+  BudgetExceeded,
+  RunawaySession,
+  BlockStarted,
+  BlockEndingSoon,
+  BlockReset,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Alert {
+  pub kind: AlertKind,
+  pub severity: AlertSeverity,
+  pub title: String,
+  pub message: String,
+  pub trigger_field: String,
+  pub trigger_value: f32,
+  pub threshold: f32,
+  pub stats: UsageStats,
+  pub timestamp: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AlertRules {
+  pub daily_budget_usd: Option<f32>,
+  pub weekly_budget_usd: Option<f32>,
+  pub monthly_budget_usd: Option<f32>,
+  pub block_budget_usd: Option<f32>,
+  pub budget_warning_percent: f32,
+  pub runaway_burn_rate: Option<f32>,
+  pub block_start_alerts: bool,
+  /// Minutes before the active block ends to send a heads-up.
+  pub block_ending_minutes: Option<u32>,
+  pub block_reset_alerts: bool,
+}
+
+impl Default for AlertRules {
+  fn default() -> Self {
+    Self {
+      daily_budget_usd: None,
+      weekly_budget_usd: None,
+      monthly_budget_usd: None,
+      block_budget_usd: None,
+      budget_warning_percent: 80.0,
+      runaway_burn_rate: None,
+      block_start_alerts: false,
+      block_ending_minutes: None,
+      block_reset_alerts: true,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookPreset {
+  Slack,
+  Discord,
+  Custom,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct WebhookConfig {
+  pub name: String,
+  pub url: String,
+  pub preset: WebhookPreset,
+  pub payload_template: Option<serde_json::Value>,
+  pub headers: BTreeMap<String, String>,
+  pub min_severity: AlertSeverity,
+  pub timeout_secs: u64,
+  pub max_retries: u32,
+  pub enabled: bool,
+}
+
+impl Default for WebhookConfig {
+  fn default() -> Self {
+    Self {
+      name: "webhook".to_string(),
+      url: String::new(),
+      preset: WebhookPreset::Slack,
+      payload_template: None,
+      headers: BTreeMap::new(),
+      min_severity: AlertSeverity::Warning,
+      timeout_secs: 10,
+      max_retries: 3,
+      enabled: true,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+  None,
+  StartTls,
+  Tls,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestSchedule {
+  Off,
+  Daily,
+  Weekly,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SmtpConfig {
+  pub enabled: bool,
+  pub host: String,
+  pub port: u16,
+  pub security: SmtpSecurity,
+  pub username: Option<String>,
+  pub password: Option<String>,
+  pub from: String,
+  pub to: Vec<String>,
+  pub timeout_secs: u64,
+  pub send_alerts: bool,
+  pub min_severity: AlertSeverity,
+  pub digest: DigestSchedule,
+  /// Local hour (0-23) at which the digest is sent.
+  pub digest_hour: u32,
+  /// Day of the week for weekly digests, 0 = Monday.
+  pub digest_weekday: u32,
+}
+
+impl Default for SmtpConfig {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      host: "localhost".to_string(),
+      port: 587,
+      security: SmtpSecurity::StartTls,
+      username: None,
+      password: None,
+      from: String::new(),
+      to: Vec::new(),
+      timeout_secs: 15,
+      send_alerts: true,
+      min_severity: AlertSeverity::Critical,
+      digest: DigestSchedule::Off,
+      digest_hour: 18,
+      digest_weekday: 4,
+    }
+  }
+}
+
+/// Sound files per alert severity; `None` plays the bundled default.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SoundConfig {
+  pub enabled: bool,
+  pub info: Option<String>,
+  pub warning: Option<String>,
+  pub critical: Option<String>,
+}
+
+impl Default for SoundConfig {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      info: None,
+      warning: None,
+      critical: None,
+    }
+  }
+}
+
+impl SoundConfig {
+  pub fn path_for(&self, severity: AlertSeverity) -> Option<&str> {
+    match severity {
+      AlertSeverity::Info => self.info.as_deref(),
+      AlertSeverity::Warning => self.warning.as_deref(),
+      AlertSeverity::Critical => self.critical.as_deref(),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct NotificationConfig {
+  pub rules: AlertRules,
+  pub webhooks: Vec<WebhookConfig>,
+  pub smtp: Option<SmtpConfig>,
+  pub sounds: SoundConfig,
+}
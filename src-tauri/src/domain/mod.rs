@@ -1,2 +1,5 @@
 pub mod entities;
-pub mod repository;
\ No newline at end of file
+pub mod notifier;
+pub mod policy;
+pub mod repository;
+pub mod settings;
\ No newline at end of file
@@ -0,0 +1,8 @@
+use async_trait::async_trait;
+use super::entities::Alert;
+
+#[async_trait]
+pub trait Notifier {
+  fn name(&self) -> String;
+  async fn notify(&self, alert: &Alert) -> Result<(), String>;
+}
@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use super::entities::{PlanConfig, WebhookConfig};
+use super::settings::{AppSettings, Profile};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicyBudgets {
+  pub daily_usd: Option<f32>,
+  pub weekly_usd: Option<f32>,
+  pub monthly_usd: Option<f32>,
+  pub block_usd: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicyAlerts {
+  pub budget_warning_percent: Option<f32>,
+  pub runaway_burn_rate: Option<f32>,
+  pub block_ending_minutes: Option<u32>,
+  pub block_reset_alerts: Option<bool>,
+  pub block_start_alerts: Option<bool>,
+}
+
+/// Organization policy. Every value is a floor on strictness: users may be
+/// stricter than the policy but can never relax or disable what it sets.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+  pub budgets: PolicyBudgets,
+  pub alerts: PolicyAlerts,
+  pub plan: Option<PlanConfig>,
+  pub webhooks: Vec<WebhookConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LockRule {
+  /// The user value may be lower, never higher.
+  Maximum,
+  /// The user value may be higher, never lower.
+  Minimum,
+  /// The value cannot be changed.
+  Fixed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LockedSetting {
+  /// Dotted path into `AppSettings`, e.g. `profiles.*.notifications.rules.daily_budget_usd`.
+  pub path: String,
+  pub rule: LockRule,
+  pub value: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PolicyStatus {
+  pub source: Option<String>,
+  pub error: Option<String>,
+  pub locked: Vec<LockedSetting>,
+}
+
+impl Policy {
+  pub fn validate(&self) -> Result<(), String> {
+    let budgets = [self.budgets.daily_usd, self.budgets.weekly_usd, self.budgets.monthly_usd, self.budgets.block_usd];
+    if budgets.iter().flatten().any(|budget| *budget <= 0.0) {
+      return Err("policy budgets must be greater than zero".to_string());
+    }
+    if self.alerts.budget_warning_percent.is_some_and(|percent| !(1.0..=100.0).contains(&percent)) {
+      return Err("policy budget_warning_percent must be between 1 and 100".to_string());
+    }
+    if self.alerts.runaway_burn_rate.is_some_and(|rate| rate <= 0.0) {
+      return Err("policy runaway_burn_rate must be greater than zero".to_string());
+    }
+    for webhook in &self.webhooks {
+      if !(webhook.url.starts_with("http://") || webhook.url.starts_with("https://")) {
+        return Err(format!("policy webhook '{}' needs an http(s) URL", webhook.name));
+      }
+    }
+    Ok(())
+  }
+
+  /// Merges the policy over user settings, returning the effective settings.
+  /// Every profile is constrained, so switching profiles cannot escape it.
+  pub fn apply(&self, user: &AppSettings) -> AppSettings {
+    let mut settings = user.clone();
+    for profile in settings.profiles.values_mut() {
+      self.apply_to_profile(profile);
+    }
+    settings
+  }
+
+  fn apply_to_profile(&self, profile: &mut Profile) {
+    let rules = &mut profile.notifications.rules;
+
+    cap(&mut rules.daily_budget_usd, self.budgets.daily_usd);
+    cap(&mut rules.weekly_budget_usd, self.budgets.weekly_usd);
+    cap(&mut rules.monthly_budget_usd, self.budgets.monthly_usd);
+    cap(&mut rules.block_budget_usd, self.budgets.block_usd);
+    if let Some(percent) = self.alerts.budget_warning_percent {
+      rules.budget_warning_percent = rules.budget_warning_percent.min(percent);
+    }
+    cap(&mut rules.runaway_burn_rate, self.alerts.runaway_burn_rate);
+    if let Some(minutes) = self.alerts.block_ending_minutes {
+      rules.block_ending_minutes = Some(rules.block_ending_minutes.map_or(minutes, |user| user.max(minutes)));
+    }
+    if self.alerts.block_reset_alerts == Some(true) {
+      rules.block_reset_alerts = true;
+    }
+    if self.alerts.block_start_alerts == Some(true) {
+      rules.block_start_alerts = true;
+    }
+
+    if let Some(plan) = &self.plan {
+      profile.plan = plan.clone();
+    }
+
+    // Policy webhooks replace user webhooks of the same name and cannot be disabled.
+    let webhooks = &mut profile.notifications.webhooks;
+    webhooks.retain(|webhook| !self.webhooks.iter().any(|locked| locked.name == webhook.name));
+    webhooks.extend(self.webhooks.iter().cloned().map(|webhook| WebhookConfig { enabled: true, ..webhook }));
+  }
+
+  pub fn locked_settings(&self) -> Vec<LockedSetting> {
+    let mut locked = Vec::new();
+    let mut push = |path: &str, rule: LockRule, value: Value| {
+      locked.push(LockedSetting { path: path.to_string(), rule, value });
+    };
+
+    let budgets = [
+      ("daily_budget_usd", self.budgets.daily_usd),
+      ("weekly_budget_usd", self.budgets.weekly_usd),
+      ("monthly_budget_usd", self.budgets.monthly_usd),
+      ("block_budget_usd", self.budgets.block_usd),
+    ];
+    for (field, budget) in budgets {
+      if let Some(budget) = budget {
+        push(&format!("profiles.*.notifications.rules.{}", field), LockRule::Maximum, json!(budget));
+      }
+    }
+    if let Some(percent) = self.alerts.budget_warning_percent {
+      push("profiles.*.notifications.rules.budget_warning_percent", LockRule::Maximum, json!(percent));
+    }
+    if let Some(rate) = self.alerts.runaway_burn_rate {
+      push("profiles.*.notifications.rules.runaway_burn_rate", LockRule::Maximum, json!(rate));
+    }
+    if let Some(minutes) = self.alerts.block_ending_minutes {
+      push("profiles.*.notifications.rules.block_ending_minutes", LockRule::Minimum, json!(minutes));
+    }
+    if self.alerts.block_reset_alerts == Some(true) {
+      push("profiles.*.notifications.rules.block_reset_alerts", LockRule::Fixed, json!(true));
+    }
+    if self.alerts.block_start_alerts == Some(true) {
+      push("profiles.*.notifications.rules.block_start_alerts", LockRule::Fixed, json!(true));
+    }
+    if let Some(plan) = &self.plan {
+      push("profiles.*.plan", LockRule::Fixed, json!(plan));
+    }
+    for webhook in &self.webhooks {
+      push(&format!("profiles.*.notifications.webhooks.{}", webhook.name), LockRule::Fixed, json!(webhook.url));
+    }
+    locked
+  }
+}
+
+fn cap(user: &mut Option<f32>, policy: Option<f32>) {
+  if let Some(limit) = policy {
+    *user = Some(user.map_or(limit, |value| value.min(limit)));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::domain::entities::AlertRules;
+  use super::*;
+
+  fn webhook(name: &str, url: &str, enabled: bool) -> WebhookConfig {
+    WebhookConfig { name: name.to_string(), url: url.to_string(), enabled, ..WebhookConfig::default() }
+  }
+
+  fn policy() -> Policy {
+    Policy {
+      budgets: PolicyBudgets { daily_usd: Some(20.0), weekly_usd: Some(100.0), ..PolicyBudgets::default() },
+      alerts: PolicyAlerts {
+        budget_warning_percent: Some(75.0),
+        runaway_burn_rate: Some(5000.0),
+        block_ending_minutes: Some(15),
+        block_reset_alerts: Some(true),
+        block_start_alerts: Some(true),
+      },
+      plan: None,
+      webhooks: vec![webhook("platform", "https://hooks.example.com/platform", true)],
+    }
+  }
+
+  /// User settings with two profiles carrying the same rules and webhooks.
+  fn user(rules: AlertRules, webhooks: Vec<WebhookConfig>) -> AppSettings {
+    let mut settings = AppSettings::default();
+    let profile = settings.profiles.values_mut().next().unwrap();
+    profile.notifications.rules = rules;
+    profile.notifications.webhooks = webhooks;
+    let profile = profile.clone();
+    settings.profiles.insert("work".to_string(), profile);
+    settings
+  }
+
+  #[test]
+  fn caps_looser_user_values_in_every_profile() {
+    let rules = AlertRules {
+      daily_budget_usd: Some(50.0),
+      weekly_budget_usd: None,
+      budget_warning_percent: 90.0,
+      runaway_burn_rate: Some(8000.0),
+      block_ending_minutes: Some(5),
+      block_reset_alerts: false,
+      block_start_alerts: false,
+      ..AlertRules::default()
+    };
+    let settings = policy().apply(&user(rules, Vec::new()));
+    for profile in settings.profiles.values() {
+      let rules = &profile.notifications.rules;
+      assert_eq!(rules.daily_budget_usd, Some(20.0));
+      assert_eq!(rules.weekly_budget_usd, Some(100.0));
+      assert_eq!(rules.budget_warning_percent, 75.0);
+      assert_eq!(rules.runaway_burn_rate, Some(5000.0));
+      assert_eq!(rules.block_ending_minutes, Some(15));
+      assert!(rules.block_reset_alerts);
+      assert!(rules.block_start_alerts);
+    }
+  }
+
+  #[test]
+  fn keeps_stricter_user_values() {
+    let rules = AlertRules {
+      daily_budget_usd: Some(10.0),
+      weekly_budget_usd: Some(60.0),
+      budget_warning_percent: 50.0,
+      runaway_burn_rate: Some(1000.0),
+      block_ending_minutes: Some(30),
+      ..AlertRules::default()
+    };
+    let settings = policy().apply(&user(rules, Vec::new()));
+    let rules = &settings.profile().notifications.rules;
+    assert_eq!(rules.daily_budget_usd, Some(10.0));
+    assert_eq!(rules.weekly_budget_usd, Some(60.0));
+    assert_eq!(rules.budget_warning_percent, 50.0);
+    assert_eq!(rules.runaway_burn_rate, Some(1000.0));
+    assert_eq!(rules.block_ending_minutes, Some(30));
+  }
+
+  #[test]
+  fn policy_webhooks_replace_user_ones_of_the_same_name() {
+    let webhooks = vec![
+      webhook("platform", "https://example.com/elsewhere", false),
+      webhook("mine", "https://example.com/mine", false),
+    ];
+    let settings = policy().apply(&user(AlertRules::default(), webhooks));
+    for profile in settings.profiles.values() {
+      let webhooks = &profile.notifications.webhooks;
+      let platform: Vec<_> = webhooks.iter().filter(|webhook| webhook.name == "platform").collect();
+      assert_eq!(platform.len(), 1);
+      assert_eq!(platform[0].url, "https://hooks.example.com/platform");
+      assert!(platform[0].enabled);
+      assert!(webhooks.iter().any(|webhook| webhook.name == "mine" && !webhook.enabled));
+    }
+  }
+
+  #[test]
+  fn policy_webhooks_cannot_be_disabled() {
+    let policy = Policy { webhooks: vec![webhook("platform", "https://hooks.example.com/platform", false)], ..policy() };
+    let settings = policy.apply(&user(AlertRules::default(), Vec::new()));
+    assert!(settings.profile().notifications.webhooks.iter().all(|webhook| webhook.enabled));
+  }
+
+  #[test]
+  fn a_policy_plan_replaces_the_user_plan() {
+    let plan = PlanConfig { block_token_limit: Some(500_000), auto_detect_block_limit: false, ..PlanConfig::default() };
+    let policy = Policy { plan: Some(plan), ..Policy::default() };
+    let mut user = user(AlertRules::default(), Vec::new());
+    user.profiles.values_mut().for_each(|profile| profile.plan.block_token_limit = Some(9_000_000));
+    for profile in policy.apply(&user).profiles.values() {
+      assert_eq!(profile.plan.block_token_limit, Some(500_000));
+      assert!(!profile.plan.auto_detect_block_limit);
+    }
+  }
+
+  #[test]
+  fn an_empty_policy_changes_nothing() {
+    let rules = AlertRules { daily_budget_usd: Some(50.0), ..AlertRules::default() };
+    let user = user(rules, vec![webhook("mine", "https://example.com/mine", true)]);
+    let settings = Policy::default().apply(&user);
+    assert_eq!(settings.profile().notifications.rules.daily_budget_usd, Some(50.0));
+    assert_eq!(settings.profile().notifications.webhooks.len(), 1);
+  }
+}
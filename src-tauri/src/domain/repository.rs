@@ -1,8 +1,12 @@
 use async_trait::async_trait;
-use super::entities::{UsageStats, UsagePeriodSummary};
+use super::entities::{DailyUsage, SessionUsage, UsageBlock, UsageStats, UsagePeriodSummary};
 
 #[async_trait]
 pub trait UsageRepository {
   async fn get_claude_usage(&self) -> Result<UsageStats, String>;
   async fn get_usage_summary(&self, period: String) -> Result<UsagePeriodSummary, String>;
-}
\ No newline at end of file
+  async fn get_blocks(&self) -> Result<Vec<UsageBlock>, String>;
+  /// Every recorded day, oldest first, with the per-model split.
+  async fn get_daily_usage(&self) -> Result<Vec<DailyUsage>, String>;
+  async fn get_sessions(&self) -> Result<Vec<SessionUsage>, String>;
+}
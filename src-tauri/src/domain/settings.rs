@@ -0,0 +1,397 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use serde::{Deserialize, Serialize};
+use super::entities::{DigestSchedule, NotificationConfig, PlanConfig};
+
+/// Current on-disk schema version of `AppSettings`.
+pub const SETTINGS_VERSION: u32 = 2;
+
+/// Profile created for fresh installs and for files migrated from version 1.
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct WarningThresholds {
+  pub warning: f32,
+  pub critical: f32,
+  pub danger: f32,
+}
+
+impl Default for WarningThresholds {
+  fn default() -> Self {
+    Self {
+      warning: 70.0,
+      critical: 85.0,
+      danger: 95.0,
+    }
+  }
+}
+
+/// Where the popup opens on the chosen monitor.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PopupAnchor {
+  /// Below (or above) the tray icon, or at the cursor when the tray position is unknown.
+  #[default]
+  Tray,
+  TopRight,
+  BottomRight,
+  NearCursor,
+}
+
+/// When the popup hides on its own. Pinning it from the popup behaves like `Never` until unpinned.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoHide {
+  /// As soon as another window takes focus.
+  #[default]
+  OnBlur,
+  /// After `auto_hide_idle_secs` without the pointer moving over it.
+  Idle,
+  /// Once the pointer has been over the popup and moves off it.
+  PointerLeave,
+  /// Only from the tray, Escape or a command.
+  Never,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct WindowSettings {
+  pub width: f64,
+  pub height: f64,
+  pub anchor: PopupAnchor,
+  /// Reopen where the popup was last dragged to on the same monitor setup.
+  pub remember_position: bool,
+  pub auto_hide: AutoHide,
+  pub auto_hide_idle_secs: u32,
+}
+
+impl Default for WindowSettings {
+  fn default() -> Self {
+    Self {
+      width: 400.0,
+      height: 580.0,
+      anchor: PopupAnchor::default(),
+      remember_position: true,
+      auto_hide: AutoHide::default(),
+      auto_hide_idle_secs: 30,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayIconStyle {
+  #[default]
+  Ring,
+  Bar,
+}
+
+/// Which percentage the tray gauge shows.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayGaugeMetric {
+  /// Share of the plan's block token limit.
+  #[default]
+  Block,
+  /// Active block cost against the profile's `block_budget_usd`.
+  Budget,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct TraySettings {
+  pub icon_style: TrayIconStyle,
+  pub gauge_metric: TrayGaugeMetric,
+  /// Template icon that follows the menubar's dark or light appearance.
+  pub monochrome: bool,
+  /// Status template for the tray tooltip; empty hides it.
+  pub tooltip_template: String,
+  /// Status template for the text next to the icon (macOS and Linux); empty hides it.
+  pub title_template: String,
+}
+
+impl Default for TraySettings {
+  fn default() -> Self {
+    Self {
+      icon_style: TrayIconStyle::default(),
+      gauge_metric: TrayGaugeMetric::default(),
+      monochrome: false,
+      tooltip_template: "{session_cost:$} · {burn_rate}/m · {time_left}".to_string(),
+      title_template: "{session_cost:$}".to_string(),
+    }
+  }
+}
+
+/// Display currency; costs are converted from USD with a fixed rate.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct CurrencyConfig {
+  pub code: String,
+  pub symbol: String,
+  /// Units of this currency per US dollar.
+  pub usd_rate: f32,
+}
+
+impl Default for CurrencyConfig {
+  fn default() -> Self {
+    Self {
+      code: "USD".to_string(),
+      symbol: "$".to_string(),
+      usd_rate: 1.0,
+    }
+  }
+}
+
+impl CurrencyConfig {
+  pub fn convert(&self, usd: f32) -> f32 {
+    usd * self.usd_rate
+  }
+
+  pub fn format(&self, usd: f32) -> String {
+    format!("{}{:.2}", self.symbol, self.convert(usd))
+  }
+}
+
+/// A named configuration: which Claude data to read and how to alert on it.
+/// Budgets live in `notifications.rules`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Profile {
+  /// Claude data directories passed to ccusage; empty uses its defaults.
+  pub data_roots: Vec<String>,
+  pub plan: PlanConfig,
+  pub currency: CurrencyConfig,
+  pub notifications: NotificationConfig,
+}
+
+impl Profile {
+  /// Data roots with a leading `~` expanded to the home directory.
+  pub fn expanded_data_roots(&self) -> Vec<PathBuf> {
+    self.data_roots.iter().map(|root| expand_home(root)).collect()
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    if let Some(missing) = self.expanded_data_roots().iter().find(|root| !root.is_dir()) {
+      return Err(format!("data root {} is not a directory", missing.display()));
+    }
+    if self.currency.code.trim().is_empty() || !self.currency.usd_rate.is_finite() || self.currency.usd_rate <= 0.0 {
+      return Err("currency needs a code and a usd_rate greater than zero".to_string());
+    }
+
+    let plan = &self.plan;
+    if plan.block_token_limit == Some(0) || plan.weekly_token_limit == Some(0) {
+      return Err("plan token limits must be greater than zero".to_string());
+    }
+
+    let rules = &self.notifications.rules;
+    if !(1.0..=100.0).contains(&rules.budget_warning_percent) {
+      return Err("budget_warning_percent must be between 1 and 100".to_string());
+    }
+    let budgets = [rules.daily_budget_usd, rules.weekly_budget_usd, rules.monthly_budget_usd, rules.block_budget_usd];
+    if budgets.iter().flatten().any(|budget| *budget <= 0.0) {
+      return Err("budgets must be greater than zero".to_string());
+    }
+    if rules.runaway_burn_rate.is_some_and(|rate| rate <= 0.0) {
+      return Err("runaway_burn_rate must be greater than zero".to_string());
+    }
+
+    for webhook in self.notifications.webhooks.iter().filter(|webhook| webhook.enabled) {
+      if !(webhook.url.starts_with("http://") || webhook.url.starts_with("https://")) {
+        return Err(format!("webhook '{}' needs an http(s) URL", webhook.name));
+      }
+    }
+    if let Some(smtp) = self.notifications.smtp.as_ref().filter(|smtp| smtp.enabled) {
+      if smtp.digest_hour > 23 {
+        return Err("digest_hour must be between 0 and 23".to_string());
+      }
+      if smtp.digest == DigestSchedule::Weekly && smtp.digest_weekday > 6 {
+        return Err("digest_weekday must be between 0 (Monday) and 6 (Sunday)".to_string());
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Global keyboard shortcut that toggles the popup at the pointer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ShortcutSettings {
+  pub enabled: bool,
+  /// Accelerator such as `CommandOrControl+Shift+U`.
+  pub accelerator: String,
+}
+
+impl Default for ShortcutSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      accelerator: "CommandOrControl+Shift+U".to_string(),
+    }
+  }
+}
+
+/// Opt-in HTTP API on 127.0.0.1 for scripts and other local tools.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ApiSettings {
+  pub enabled: bool,
+  pub port: u16,
+  /// Bearer token clients must send; `None` uses the generated `api-token` file.
+  pub token: Option<String>,
+}
+
+impl Default for ApiSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      port: 47821,
+      token: None,
+    }
+  }
+}
+
+/// Prometheus exposition of the polled usage.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct MetricsSettings {
+  /// Serve `/metrics` on the local API.
+  pub endpoint: bool,
+  /// `.prom` file rewritten after every poll, e.g. for node_exporter's textfile collector.
+  pub textfile: Option<String>,
+}
+
+impl MetricsSettings {
+  pub fn expanded_textfile(&self) -> Option<PathBuf> {
+    self.textfile.as_deref().map(expand_home)
+  }
+}
+
+/// Expands `~` and a leading `~/` (or `~\` on Windows) to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+  let rest = if path == "~" {
+    Some("")
+  } else {
+    path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\").filter(|_| cfg!(windows)))
+  };
+  match (rest, dirs::home_dir()) {
+    (Some(""), Some(home)) => home,
+    (Some(rest), Some(home)) => home.join(rest),
+    _ => PathBuf::from(path),
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AppSettings {
+  pub version: u32,
+  /// How often the popup refreshes while visible.
+  pub refresh_interval_secs: u64,
+  /// How often the background monitor polls usage for alerts.
+  pub poll_interval_secs: u64,
+  pub window: WindowSettings,
+  pub thresholds: WarningThresholds,
+  pub tray: TraySettings,
+  pub shortcut: ShortcutSettings,
+  pub api: ApiSettings,
+  pub metrics: MetricsSettings,
+  pub default_period: String,
+  /// Name of the profile the repository and monitor currently use.
+  pub active_profile: String,
+  pub profiles: BTreeMap<String, Profile>,
+}
+
+impl Default for AppSettings {
+  fn default() -> Self {
+    Self {
+      version: SETTINGS_VERSION,
+      refresh_interval_secs: 5,
+      poll_interval_secs: 60,
+      window: WindowSettings::default(),
+      thresholds: WarningThresholds::default(),
+      tray: TraySettings::default(),
+      shortcut: ShortcutSettings::default(),
+      api: ApiSettings::default(),
+      metrics: MetricsSettings::default(),
+      default_period: "day".to_string(),
+      active_profile: DEFAULT_PROFILE.to_string(),
+      profiles: BTreeMap::from([(DEFAULT_PROFILE.to_string(), Profile::default())]),
+    }
+  }
+}
+
+impl AppSettings {
+  /// The active profile. Settings that skipped `validate` may name a missing
+  /// one; the default profile, then any profile, then defaults stand in.
+  pub fn profile(&self) -> &Profile {
+    static FALLBACK: LazyLock<Profile> = LazyLock::new(Profile::default);
+    self.profiles.get(&self.active_profile)
+      .or_else(|| self.profiles.get(DEFAULT_PROFILE))
+      .or_else(|| self.profiles.values().next())
+      .unwrap_or(&FALLBACK)
+  }
+
+  pub fn profile_mut(&mut self) -> Result<&mut Profile, String> {
+    self.profiles.get_mut(&self.active_profile)
+      .ok_or_else(|| format!("active_profile '{}' does not exist", self.active_profile))
+  }
+
+  pub fn validate(&self) -> Result<(), String> {
+    if !(1..=3600).contains(&self.refresh_interval_secs) {
+      return Err("refresh_interval_secs must be between 1 and 3600".to_string());
+    }
+    if !(10..=3600).contains(&self.poll_interval_secs) {
+      return Err("poll_interval_secs must be between 10 and 3600".to_string());
+    }
+    if !(200.0..=2000.0).contains(&self.window.width) || !(200.0..=2000.0).contains(&self.window.height) {
+      return Err("window width and height must be between 200 and 2000".to_string());
+    }
+    if !(1..=3600).contains(&self.window.auto_hide_idle_secs) {
+      return Err("window.auto_hide_idle_secs must be between 1 and 3600".to_string());
+    }
+    if self.api.port < 1024 {
+      return Err("api.port must be between 1024 and 65535".to_string());
+    }
+    if self.api.token.as_ref().is_some_and(|token| token.trim().len() < 16) {
+      return Err("api.token must be at least 16 characters".to_string());
+    }
+    if self.metrics.textfile.as_ref().is_some_and(|path| !path.ends_with(".prom")) {
+      return Err("metrics.textfile must end in .prom".to_string());
+    }
+
+    let thresholds = &self.thresholds;
+    if !(0.0 < thresholds.warning && thresholds.warning < thresholds.critical
+      && thresholds.critical < thresholds.danger && thresholds.danger <= 100.0) {
+      return Err("thresholds must satisfy 0 < warning < critical < danger <= 100".to_string());
+    }
+    if !matches!(self.default_period.as_str(), "day" | "week" | "month") {
+      return Err(format!("default_period must be day, week or month, got '{}'", self.default_period));
+    }
+
+    if !self.profiles.contains_key(&self.active_profile) {
+      return Err(format!("active_profile '{}' does not exist", self.active_profile));
+    }
+    for (name, profile) in &self.profiles {
+      if name.trim().is_empty() {
+        return Err("profile names must not be empty".to_string());
+      }
+      profile.validate().map_err(|e| format!("profile '{}': {}", name, e))?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn expands_the_home_directory() {
+    let home = dirs::home_dir().unwrap();
+    assert_eq!(expand_home("~"), home);
+    assert_eq!(expand_home("~/.claude"), home.join(".claude"));
+    assert_eq!(expand_home("/srv/claude"), PathBuf::from("/srv/claude"));
+    assert_eq!(expand_home("~other/.claude"), PathBuf::from("~other/.claude"));
+  }
+}
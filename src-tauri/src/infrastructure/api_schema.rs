@@ -0,0 +1,118 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use crate::domain::entities::{Alert, UsageBlock, UsageSnapshot};
+
+/// Bumped on any breaking change to a response body; additions keep the version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Every successful response wraps its payload in this envelope.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Envelope<T> {
+  pub schema_version: u32,
+  /// RFC 3339 time the response was produced.
+  pub generated_at: String,
+  pub data: T,
+}
+
+impl<T> Envelope<T> {
+  pub fn new(data: T) -> Self {
+    Self {
+      schema_version: SCHEMA_VERSION,
+      generated_at: Local::now().to_rfc3339(),
+      data,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ErrorBody {
+  pub schema_version: u32,
+  pub error: String,
+}
+
+impl ErrorBody {
+  pub fn new(error: impl Into<String>) -> Self {
+    Self { schema_version: SCHEMA_VERSION, error: error.into() }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Health {
+  pub status: String,
+  pub app_version: String,
+  pub schema_version: u32,
+}
+
+/// What a stream client can subscribe to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Topic {
+  Usage,
+  Alerts,
+  Blocks,
+}
+
+impl Topic {
+  pub const ALL: [Topic; 3] = [Topic::Usage, Topic::Alerts, Topic::Blocks];
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Topic::Usage => "usage",
+      Topic::Alerts => "alerts",
+      Topic::Blocks => "blocks",
+    }
+  }
+
+  /// Parses a comma-separated list such as `usage,alerts`.
+  pub fn parse_list(list: &str) -> Result<Vec<Topic>, String> {
+    list.split(',')
+      .map(str::trim)
+      .filter(|name| !name.is_empty())
+      .map(|name| Topic::ALL.into_iter()
+        .find(|topic| topic.name() == name)
+        .ok_or_else(|| format!("unknown topic '{}', expected usage, alerts or blocks", name)))
+      .collect()
+  }
+}
+
+/// One pushed update; serialized as `"topic"` and `"data"` fields.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "topic", content = "data", rename_all = "lowercase")]
+pub enum StreamEvent {
+  /// The backend polled fresh stats.
+  Usage(UsageSnapshot),
+  /// An alert fired; published before webhooks and email are tried, and
+  /// not at all while alerts are snoozed.
+  Alerts(Alert),
+  /// The active block started, changed or ended (`null`).
+  Blocks(Option<UsageBlock>),
+}
+
+impl StreamEvent {
+  pub fn topic(&self) -> Topic {
+    match self {
+      StreamEvent::Usage(_) => Topic::Usage,
+      StreamEvent::Alerts(_) => Topic::Alerts,
+      StreamEvent::Blocks(_) => Topic::Blocks,
+    }
+  }
+}
+
+/// A stream message: the event plus the schema version and send time.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StreamMessage {
+  pub schema_version: u32,
+  pub generated_at: String,
+  #[serde(flatten)]
+  pub event: StreamEvent,
+}
+
+impl StreamMessage {
+  pub fn new(event: StreamEvent) -> Self {
+    Self {
+      schema_version: SCHEMA_VERSION,
+      generated_at: Local::now().to_rfc3339(),
+      event,
+    }
+  }
+}
@@ -0,0 +1,65 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use super::settings_store::APP_IDENTIFIER;
+
+const TOKEN_FILE: &str = "api-token";
+
+pub fn default_path() -> Result<PathBuf, String> {
+  let config_dir = dirs::config_dir().ok_or("Could not determine the platform config directory")?;
+  Ok(config_dir.join(APP_IDENTIFIER).join(TOKEN_FILE))
+}
+
+/// The token from `api.token`, or else the one in the token file.
+pub fn resolve(configured: Option<&str>) -> Result<String, String> {
+  match configured {
+    Some(token) => Ok(token.trim().to_string()),
+    None => read(),
+  }
+}
+
+pub fn read() -> Result<String, String> {
+  let path = default_path()?;
+  fs::read_to_string(&path)
+    .map(|token| token.trim().to_string())
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Reads the token file, generating a random token on first use. The file
+/// is readable by the current user only, so other accounts on the machine
+/// cannot query the API.
+pub fn load_or_create() -> Result<String, String> {
+  if let Ok(token) = read() {
+    if !token.is_empty() {
+      return Ok(token);
+    }
+  }
+  let path = default_path()?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  let mut bytes = [0u8; 24];
+  getrandom::getrandom(&mut bytes).map_err(|e| format!("Failed to generate API token: {}", e))?;
+  let token: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+  // Created owner-only so the token is never readable under the umask, not
+  // even briefly.
+  let mut options = fs::OpenOptions::new();
+  options.write(true).create(true).truncate(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  let mut file = options.open(&path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+  // `mode` only applies to new files; an existing empty one is restricted before writing.
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    file.set_permissions(fs::Permissions::from_mode(0o600))
+      .map_err(|e| format!("Failed to restrict {}: {}", path.display(), e))?;
+  }
+  file.write_all(token.as_bytes()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+  println!("🔑 Generated local API token in {}", path.display());
+  Ok(token)
+}
@@ -0,0 +1,19 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+
+/// Writes `contents` to a sibling temporary file and renames it over `path`,
+/// creating the parent directory first. Readers see either the old or the
+/// new file, never a partial one, and a crash leaves the old file in place.
+/// The temporary name ends in `.tmp`, so collectors that match on the final
+/// extension (e.g. `*.prom`) never pick it up.
+pub fn write(path: &Path, contents: &str) -> Result<(), String> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  let mut tmp_name = path.file_name().map(OsString::from).unwrap_or_default();
+  tmp_name.push(".tmp");
+  let tmp_path = path.with_file_name(tmp_name);
+  fs::write(&tmp_path, contents).map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+  fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
@@ -1,13 +1,29 @@
 use std::process::Command;
 use std::path::PathBuf;
-use chrono::{Datelike, Local, NaiveDate};
+use std::sync::atomic::{AtomicBool, Ordering};
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
 use serde::Deserialize;
 use async_trait::async_trait;
 use crate::domain::{
-  entities::{UsageStats, UsagePeriodSummary},
+  entities::{DailyUsage, ModelUsage, SessionUsage, TokenBreakdown, UsageBlock, UsageStats, UsagePeriodSummary},
   repository::UsageRepository,
 };
 
+/// Path discovery is chatty; the CLI turns it off so only its results reach stdout.
+static LOG_DISCOVERY: AtomicBool = AtomicBool::new(true);
+
+pub fn set_discovery_logging(enabled: bool) {
+  LOG_DISCOVERY.store(enabled, Ordering::Relaxed);
+}
+
+macro_rules! discovery_log {
+  ($($arg:tt)*) => {
+    if LOG_DISCOVERY.load(Ordering::Relaxed) {
+      println!($($arg)*);
+    }
+  };
+}
+
 #[derive(Deserialize, Debug)]
 #[allow(dead_code)]
 struct TokenCounts {
@@ -75,6 +91,68 @@ struct CcusageBlocksResponse {
   blocks: Vec<CcusageBlock>,
 }
 
+impl From<CcusageBlock> for UsageBlock {
+  fn from(block: CcusageBlock) -> Self {
+    Self {
+      id: block.id,
+      start_time: block.start_time,
+      end_time: block.end_time,
+      actual_end_time: block.actual_end_time,
+      is_active: block.is_active,
+      is_gap: block.is_gap,
+      entries: block.entries,
+      total_tokens: block.total_tokens,
+      cost_usd: block.cost_usd,
+      models: block.models,
+    }
+  }
+}
+
+/// Token classes as they appear on daily, session and model breakdown entries.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct CcusageTokenClasses {
+  #[serde(rename = "inputTokens")]
+  input_tokens: u64,
+  #[serde(rename = "outputTokens")]
+  output_tokens: u64,
+  #[serde(rename = "cacheCreationTokens")]
+  cache_creation_tokens: u64,
+  #[serde(rename = "cacheReadTokens")]
+  cache_read_tokens: u64,
+}
+
+impl From<&CcusageTokenClasses> for TokenBreakdown {
+  fn from(tokens: &CcusageTokenClasses) -> Self {
+    Self {
+      input_tokens: tokens.input_tokens,
+      output_tokens: tokens.output_tokens,
+      cache_creation_tokens: tokens.cache_creation_tokens,
+      cache_read_tokens: tokens.cache_read_tokens,
+    }
+  }
+}
+
+#[derive(Deserialize, Debug)]
+struct CcusageModelBreakdown {
+  #[serde(rename = "modelName")]
+  model_name: String,
+  #[serde(flatten)]
+  tokens: CcusageTokenClasses,
+  #[serde(default)]
+  cost: f64,
+}
+
+impl From<&CcusageModelBreakdown> for ModelUsage {
+  fn from(breakdown: &CcusageModelBreakdown) -> Self {
+    Self {
+      model: breakdown.model_name.clone(),
+      tokens: TokenBreakdown::from(&breakdown.tokens),
+      cost_usd: breakdown.cost,
+    }
+  }
+}
+
 #[derive(Deserialize, Debug)]
 #[allow(dead_code)]
 struct CcusageDailyEntry {
@@ -85,6 +163,10 @@ struct CcusageDailyEntry {
   total_cost: f32,
   #[serde(rename = "modelsUsed")]
   models_used: Vec<String>,
+  #[serde(flatten)]
+  tokens: CcusageTokenClasses,
+  #[serde(rename = "modelBreakdowns", default)]
+  model_breakdowns: Vec<CcusageModelBreakdown>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -92,14 +174,45 @@ struct CcusageDailyResponse {
   daily: Vec<CcusageDailyEntry>,
 }
 
-pub struct CcusageRepository;
+#[derive(Deserialize, Debug)]
+struct CcusageSessionEntry {
+  #[serde(rename = "sessionId")]
+  session_id: String,
+  #[serde(rename = "projectPath")]
+  project_path: String,
+  #[serde(rename = "lastActivity")]
+  last_activity: String,
+  #[serde(rename = "totalCost")]
+  total_cost: f64,
+  #[serde(rename = "modelsUsed", default)]
+  models_used: Vec<String>,
+  #[serde(flatten)]
+  tokens: CcusageTokenClasses,
+}
+
+#[derive(Deserialize, Debug)]
+struct CcusageSessionResponse {
+  sessions: Vec<CcusageSessionEntry>,
+}
+
+#[derive(Default)]
+pub struct CcusageRepository {
+  data_roots: Vec<PathBuf>,
+}
 
 impl CcusageRepository {
   pub fn new() -> Self {
-    Self
+    Self { data_roots: Vec::new() }
+  }
+
+  /// Reads Claude data from the given directories instead of ccusage's defaults.
+  pub fn with_data_roots(data_roots: Vec<PathBuf>) -> Self {
+    Self { data_roots }
   }
 
-  fn find_node_and_ccusage_paths(&self) -> Result<(String, String), String> {
+  /// The Node.js and ccusage executables commands will run, falling back to
+  /// bare names resolved through `PATH`.
+  pub fn find_node_and_ccusage_paths(&self) -> Result<(String, String), String> {
     let home_dir = std::env::var("HOME").map_err(|_| "Could not get HOME directory")?;
 
     // Node.js path candidates
@@ -147,18 +260,18 @@ impl CcusageRepository {
 
     // Find Node.js path
     let node_path = if let Ok(node_env) = std::env::var("NODE_PATH") {
-      println!("🔍 Using NODE_PATH environment variable: {}", node_env);
+      discovery_log!("🔍 Using NODE_PATH environment variable: {}", node_env);
       node_env
     } else {
-      println!("🔍 Searching for Node.js in {} candidates", node_candidates.len());
+      discovery_log!("🔍 Searching for Node.js in {} candidates", node_candidates.len());
       let found_node = node_candidates.into_iter()
         .find(|path| {
           let exists = PathBuf::from(path).exists();
-          println!("🔍 Checking Node.js path: {} -> {}", path, exists);
+          discovery_log!("🔍 Checking Node.js path: {} -> {}", path, exists);
           exists
         })
         .unwrap_or_else(|| {
-          println!("⚠️ No Node.js found in candidates, falling back to system PATH");
+          discovery_log!("⚠️ No Node.js found in candidates, falling back to system PATH");
           "node".to_string()
         });
       found_node
@@ -166,24 +279,24 @@ impl CcusageRepository {
 
     // Find ccusage path
     let ccusage_path = if let Ok(ccusage_env) = std::env::var("CCUSAGE_PATH") {
-      println!("🔍 Using CCUSAGE_PATH environment variable: {}", ccusage_env);
+      discovery_log!("🔍 Using CCUSAGE_PATH environment variable: {}", ccusage_env);
       ccusage_env
     } else {
-      println!("🔍 Searching for ccusage in {} candidates", ccusage_candidates.len());
+      discovery_log!("🔍 Searching for ccusage in {} candidates", ccusage_candidates.len());
       let found_ccusage = ccusage_candidates.into_iter()
         .find(|path| {
           let exists = PathBuf::from(path).exists();
-          println!("🔍 Checking ccusage path: {} -> {}", path, exists);
+          discovery_log!("🔍 Checking ccusage path: {} -> {}", path, exists);
           exists
         })
         .unwrap_or_else(|| {
-          println!("⚠️ No ccusage found in candidates, falling back to system PATH");
+          discovery_log!("⚠️ No ccusage found in candidates, falling back to system PATH");
           "ccusage".to_string()
         });
       found_ccusage
     };
 
-    println!("✅ Selected paths - Node.js: {}, ccusage: {}", node_path, ccusage_path);
+    discovery_log!("✅ Selected paths - Node.js: {}, ccusage: {}", node_path, ccusage_path);
     Ok((node_path, ccusage_path))
   }
 
@@ -202,6 +315,12 @@ impl CcusageRepository {
 
     cmd.env("PATH", enhanced_path);
     cmd.env("NODE_PATH", node_path);
+
+    // ccusage accepts a comma-separated list of Claude data directories
+    if !self.data_roots.is_empty() {
+      let roots: Vec<String> = self.data_roots.iter().map(|root| root.display().to_string()).collect();
+      cmd.env("CLAUDE_CONFIG_DIR", roots.join(","));
+    }
     cmd
   }
 
@@ -209,9 +328,9 @@ impl CcusageRepository {
     match error.kind() {
       std::io::ErrorKind::NotFound => {
         if ccusage_path == "ccusage" {
-          format!("❌ ccusage not found\n\n📋 To use Claude Token Monitor, you need to install ccusage:\n\n1️⃣ Install ccusage globally:\n   npm install -g ccusage\n\n2️⃣ Or using yarn:\n   yarn global add ccusage\n\n3️⃣ Or using pnpm:\n   pnpm add -g ccusage\n\n4️⃣ Make sure ccusage is in your PATH\n\n5️⃣ Restart Claude Token Monitor after installation\n\n💡 Alternative: Set CCUSAGE_PATH environment variable to custom installation path")
+          "❌ ccusage not found\n\n📋 To use Claude Token Monitor, you need to install ccusage:\n\n1️⃣ Install ccusage globally:\n   npm install -g ccusage\n\n2️⃣ Or using yarn:\n   yarn global add ccusage\n\n3️⃣ Or using pnpm:\n   pnpm add -g ccusage\n\n4️⃣ Make sure ccusage is in your PATH\n\n5️⃣ Restart Claude Token Monitor after installation\n\n💡 Alternative: Set CCUSAGE_PATH environment variable to custom installation path".to_string()
         } else if node_path == "node" {
-          format!("❌ Node.js not found\n\n📋 To use Claude Token Monitor, you need Node.js:\n\n1️⃣ Install Node.js from https://nodejs.org\n\n2️⃣ Or using Homebrew:\n   brew install node\n\n3️⃣ Or using a version manager:\n   • nvm: https://github.com/nvm-sh/nvm\n   • fnm: https://github.com/Schniz/fnm\n   • volta: https://volta.sh\n\n4️⃣ After installation, install ccusage:\n   npm install -g ccusage\n\n💡 Alternative: Set NODE_PATH environment variable to custom Node.js path")
+          "❌ Node.js not found\n\n📋 To use Claude Token Monitor, you need Node.js:\n\n1️⃣ Install Node.js from https://nodejs.org\n\n2️⃣ Or using Homebrew:\n   brew install node\n\n3️⃣ Or using a version manager:\n   • nvm: https://github.com/nvm-sh/nvm\n   • fnm: https://github.com/Schniz/fnm\n   • volta: https://volta.sh\n\n4️⃣ After installation, install ccusage:\n   npm install -g ccusage\n\n💡 Alternative: Set NODE_PATH environment variable to custom Node.js path".to_string()
         } else {
           format!("❌ Command execution failed\n\n🔍 Detected paths:\n• Node.js: {}\n• ccusage: {}\n\n📋 Troubleshooting:\n\n1️⃣ Verify Node.js installation:\n   {} --version\n\n2️⃣ Verify ccusage installation:\n   {} --version\n\n3️⃣ Check file permissions\n\n4️⃣ Try reinstalling ccusage:\n   npm install -g ccusage\n\n💡 Error details: {}", node_path, ccusage_path, node_path, ccusage_path, error)
         }
@@ -225,9 +344,57 @@ impl CcusageRepository {
     }
   }
 
+  fn fetch_blocks(&self, node_path: &str, ccusage_path: &str) -> Result<CcusageBlocksResponse, String> {
+    let blocks_output = self.create_command_with_env(ccusage_path, node_path)
+      .args(["blocks", "--json"])
+      .output()
+      .map_err(|e| self.create_user_friendly_error(ccusage_path, node_path, &e))?;
+
+    if !blocks_output.status.success() {
+      let stderr = String::from_utf8_lossy(&blocks_output.stderr);
+      return Err(self.create_ccusage_command_error("blocks", &stderr));
+    }
+
+    let blocks_json = String::from_utf8_lossy(&blocks_output.stdout);
+    serde_json::from_str(&blocks_json)
+      .map_err(|e| format!("Failed to parse ccusage blocks output: {}", e))
+  }
+
+  fn fetch_daily(&self, node_path: &str, ccusage_path: &str) -> Result<CcusageDailyResponse, String> {
+    let daily_output = self.create_command_with_env(ccusage_path, node_path)
+      .args(["daily", "--json", "--breakdown"])
+      .output()
+      .map_err(|e| self.create_user_friendly_error(ccusage_path, node_path, &e))?;
+
+    if !daily_output.status.success() {
+      let stderr = String::from_utf8_lossy(&daily_output.stderr);
+      return Err(self.create_ccusage_command_error("daily", &stderr));
+    }
+
+    let daily_json = String::from_utf8_lossy(&daily_output.stdout);
+    serde_json::from_str(&daily_json)
+      .map_err(|e| format!("Failed to parse ccusage daily output: {}", e))
+  }
+
+  fn fetch_sessions(&self, node_path: &str, ccusage_path: &str) -> Result<CcusageSessionResponse, String> {
+    let session_output = self.create_command_with_env(ccusage_path, node_path)
+      .args(["session", "--json"])
+      .output()
+      .map_err(|e| self.create_user_friendly_error(ccusage_path, node_path, &e))?;
+
+    if !session_output.status.success() {
+      let stderr = String::from_utf8_lossy(&session_output.stderr);
+      return Err(self.create_ccusage_command_error("session", &stderr));
+    }
+
+    let session_json = String::from_utf8_lossy(&session_output.stdout);
+    serde_json::from_str(&session_json)
+      .map_err(|e| format!("Failed to parse ccusage session output: {}", e))
+  }
+
   fn create_ccusage_command_error(&self, command: &str, stderr: &str) -> String {
     if stderr.contains("command not found") || stderr.contains("No such file") {
-      format!("❌ ccusage command not found\n\n📋 Installation required:\n\n1️⃣ Install ccusage globally:\n   npm install -g ccusage\n\n2️⃣ Verify installation:\n   ccusage --version\n\n3️⃣ Restart Claude Token Monitor\n\n💡 If using yarn or pnpm:\n   yarn global add ccusage\n   pnpm add -g ccusage")
+      "❌ ccusage command not found\n\n📋 Installation required:\n\n1️⃣ Install ccusage globally:\n   npm install -g ccusage\n\n2️⃣ Verify installation:\n   ccusage --version\n\n3️⃣ Restart Claude Token Monitor\n\n💡 If using yarn or pnpm:\n   yarn global add ccusage\n   pnpm add -g ccusage".to_string()
     } else if stderr.contains("ENOENT") {
       format!("❌ Node.js or ccusage not accessible\n\n🔍 This usually means:\n• Node.js is not installed\n• ccusage is not installed\n• PATH environment variable issue\n\n📋 Quick fix:\n\n1️⃣ Install Node.js: https://nodejs.org\n2️⃣ Install ccusage: npm install -g ccusage\n3️⃣ Restart the application\n\n💡 Error: {}", stderr)
     } else if stderr.contains("permission") || stderr.contains("EACCES") {
@@ -244,19 +411,7 @@ impl CcusageRepository {
 impl UsageRepository for CcusageRepository {
   async fn get_claude_usage(&self) -> Result<UsageStats, String> {
       let (node_path, ccusage_path) = self.find_node_and_ccusage_paths()?;
-      let blocks_output = self.create_command_with_env(&ccusage_path, &node_path)
-      .args(&["blocks", "--json"])
-      .output()
-      .map_err(|e| self.create_user_friendly_error(&ccusage_path, &node_path, &e))?;
-
-      if !blocks_output.status.success() {
-      let stderr = String::from_utf8_lossy(&blocks_output.stderr);
-      return Err(self.create_ccusage_command_error("blocks", &stderr));
-      }
-
-      let blocks_json = String::from_utf8_lossy(&blocks_output.stdout);
-      let blocks_response: CcusageBlocksResponse = serde_json::from_str(&blocks_json)
-      .map_err(|e| format!("Failed to parse ccusage blocks output: {}", e))?;
+      let blocks_response = self.fetch_blocks(&node_path, &ccusage_path)?;
 
       let active_block = blocks_response.blocks.iter().find(|block| block.is_active);
 
@@ -273,7 +428,7 @@ impl UsageRepository for CcusageRepository {
       };
 
       let daily_output = self.create_command_with_env(&ccusage_path, &node_path)
-        .args(&["daily", "--json"])
+        .args(["daily", "--json"])
         .output()
         .map_err(|e| self.create_user_friendly_error(&ccusage_path, &node_path, &e))?;
 
@@ -289,22 +444,37 @@ impl UsageRepository for CcusageRepository {
       let (daily_tokens, daily_cost) = daily_response.daily.iter()
       .find(|entry| NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").ok() == Some(today))
       .map_or((0, 0.0), |entry| (entry.total_tokens, entry.total_cost));
+      let week_start = today.checked_sub_days(chrono::Days::new(6)).unwrap_or(today);
+      let weekly_tokens = daily_response.daily.iter()
+      .filter(|entry| NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").is_ok_and(|date| date >= week_start && date <= today))
+      .map(|entry| entry.total_tokens as u64)
+      .sum();
+
+      let block_minutes_remaining = active_block
+      .and_then(|block| DateTime::parse_from_rfc3339(&block.end_time).ok())
+      .map(|end| (end.with_timezone(&Utc) - Utc::now()).num_minutes().max(0) as u32);
 
       Ok(UsageStats {
       active_session: active_block.is_some(),
       current_tokens,
       daily_tokens,
+      weekly_tokens,
       cost: daily_cost,
       model: primary_model,
       session_cost,
       burn_rate: burn_rate_value,
+      block_start_time: active_block.map(|block| block.start_time.clone()),
+      block_end_time: active_block.map(|block| block.end_time.clone()),
+      block_minutes_remaining,
+      percent_of_block_limit: None,
+      percent_of_weekly_limit: None,
       })
   }
 
   async fn get_usage_summary(&self, period: String) -> Result<UsagePeriodSummary, String> {
       let (node_path, ccusage_path) = self.find_node_and_ccusage_paths()?;
       let daily_output = self.create_command_with_env(&ccusage_path, &node_path)
-        .args(&["daily", "--json"])
+        .args(["daily", "--json"])
         .output()
         .map_err(|e| self.create_user_friendly_error(&ccusage_path, &node_path, &e))?;
 
@@ -332,12 +502,12 @@ impl UsageRepository for CcusageRepository {
       _ => (today, today, 1), // Always use today for day period
       };
 
-      let mut total_tokens = 0;
+      let mut total_tokens: u64 = 0;
       let mut total_cost = 0.0;
       for entry in &daily_response.daily {
       if let Ok(entry_date) = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d") {
           if entry_date >= start && entry_date <= end {
-        total_tokens += entry.total_tokens;
+        total_tokens += entry.total_tokens as u64;
         total_cost += entry.total_cost;
           }
       }
@@ -357,4 +527,38 @@ impl UsageRepository for CcusageRepository {
       avg_cost_per_day,
       })
   }
-}
+
+  async fn get_blocks(&self) -> Result<Vec<UsageBlock>, String> {
+      let (node_path, ccusage_path) = self.find_node_and_ccusage_paths()?;
+      let blocks_response = self.fetch_blocks(&node_path, &ccusage_path)?;
+      Ok(blocks_response.blocks.into_iter().map(UsageBlock::from).collect())
+  }
+
+  async fn get_daily_usage(&self) -> Result<Vec<DailyUsage>, String> {
+      let (node_path, ccusage_path) = self.find_node_and_ccusage_paths()?;
+      let mut days: Vec<DailyUsage> = self.fetch_daily(&node_path, &ccusage_path)?.daily.iter()
+      .map(|entry| DailyUsage {
+          date: entry.date.clone(),
+          tokens: TokenBreakdown::from(&entry.tokens),
+          cost_usd: entry.total_cost as f64,
+          models: entry.model_breakdowns.iter().map(ModelUsage::from).collect(),
+      })
+      .collect();
+      days.sort_by(|a, b| a.date.cmp(&b.date));
+      Ok(days)
+  }
+
+  async fn get_sessions(&self) -> Result<Vec<SessionUsage>, String> {
+      let (node_path, ccusage_path) = self.find_node_and_ccusage_paths()?;
+      Ok(self.fetch_sessions(&node_path, &ccusage_path)?.sessions.into_iter()
+      .map(|session| SessionUsage {
+          tokens: TokenBreakdown::from(&session.tokens),
+          session_id: session.session_id,
+          project_path: session.project_path,
+          last_activity: session.last_activity,
+          cost_usd: session.total_cost,
+          models: session.models_used,
+      })
+      .collect())
+  }
+}
\ No newline at end of file
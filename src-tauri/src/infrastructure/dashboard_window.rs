@@ -0,0 +1,25 @@
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+pub const DASHBOARD_LABEL: &str = "dashboard";
+
+/// Brings the dashboard to the front, creating it on first use. Unlike the
+/// popup it is an ordinary window: decorated, resizable and kept open on blur.
+pub fn open(app: &AppHandle) -> Result<(), String> {
+  if let Some(window) = app.get_webview_window(DASHBOARD_LABEL) {
+    let _ = window.unminimize();
+    window.show().map_err(|e| e.to_string())?;
+    return window.set_focus().map_err(|e| e.to_string());
+  }
+
+  WebviewWindowBuilder::new(app, DASHBOARD_LABEL, WebviewUrl::App("dashboard".into()))
+    .title("Claude Token Monitor Dashboard")
+    .inner_size(960.0, 680.0)
+    .min_inner_size(640.0, 480.0)
+    .resizable(true)
+    .center()
+    .focused(true)
+    .build()
+    .map_err(|e| format!("Failed to create dashboard window: {}", e))?;
+  println!("📊 Dashboard opened");
+  Ok(())
+}
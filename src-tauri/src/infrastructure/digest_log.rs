@@ -0,0 +1,35 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use chrono::NaiveDate;
+use super::{atomic_file, settings_store::APP_IDENTIFIER};
+
+const DIGEST_LOG_FILE: &str = "digest-log.json";
+
+/// The day each profile's digest last went out, so a restart neither sends
+/// it twice nor forgets a weekly one that is still owed.
+pub fn default_path() -> Result<PathBuf, String> {
+  let config_dir = dirs::config_dir().ok_or("Could not determine the platform config directory")?;
+  Ok(config_dir.join(APP_IDENTIFIER).join(DIGEST_LOG_FILE))
+}
+
+/// A missing or unreadable log counts as no digest sent yet.
+pub fn last_sent(profile: &str) -> Option<NaiveDate> {
+  read().get(profile).copied()
+}
+
+pub fn record_sent(profile: &str, date: NaiveDate) -> Result<(), String> {
+  let path = default_path()?;
+  let mut log = read();
+  log.insert(profile.to_string(), date);
+  let json = serde_json::to_string_pretty(&log).map_err(|e| format!("Failed to serialize digest log: {}", e))?;
+  atomic_file::write(&path, &json)
+}
+
+fn read() -> BTreeMap<String, NaiveDate> {
+  default_path()
+    .ok()
+    .and_then(|path| fs::read_to_string(path).ok())
+    .and_then(|contents| serde_json::from_str(&contents).ok())
+    .unwrap_or_default()
+}
@@ -0,0 +1,51 @@
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use crate::domain::entities::UsageBlock;
+use super::api_schema::StreamEvent;
+
+/// Slow clients skip ahead rather than hold back the backend.
+const CAPACITY: usize = 64;
+
+/// Fans backend updates out to stream subscribers.
+pub struct EventStream {
+  sender: broadcast::Sender<StreamEvent>,
+  /// The active block last published, to only announce changes.
+  last_block: Mutex<Option<Option<(String, u32)>>>,
+}
+
+impl EventStream {
+  pub fn new() -> Self {
+    Self { sender: broadcast::channel(CAPACITY).0, last_block: Mutex::new(None) }
+  }
+
+  pub fn subscribe(&self) -> broadcast::Receiver<StreamEvent> {
+    self.sender.subscribe()
+  }
+
+  pub fn has_subscribers(&self) -> bool {
+    self.sender.receiver_count() > 0
+  }
+
+  pub fn publish(&self, event: StreamEvent) {
+    // Failing only means nobody is listening.
+    let _ = self.sender.send(event);
+  }
+
+  /// Publishes the active block when it differs from the last one published.
+  pub fn publish_block(&self, active: Option<UsageBlock>) {
+    let key = active.as_ref().map(|block| (block.id.clone(), block.total_tokens));
+    let mut last_block = self.last_block.lock().unwrap();
+    if last_block.as_ref() == Some(&key) {
+      return;
+    }
+    *last_block = Some(key);
+    drop(last_block);
+    self.publish(StreamEvent::Blocks(active));
+  }
+}
+
+impl Default for EventStream {
+  fn default() -> Self {
+    Self::new()
+  }
+}
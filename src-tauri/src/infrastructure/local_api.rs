@@ -0,0 +1,329 @@
+use std::convert::Infallible;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use http_body_util::{combinators::UnsyncBoxBody, BodyExt, Full, StreamBody};
+use hyper::{
+  body::{Bytes, Frame, Incoming},
+  header, server::conn::http1, service::service_fn, Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use tauri::{async_runtime::JoinHandle, AppHandle, Manager};
+use tokio::{
+  net::TcpListener,
+  sync::{broadcast::error::RecvError, mpsc, watch},
+};
+use super::{
+  api_schema::{Envelope, ErrorBody, Health, StreamEvent, StreamMessage, Topic, SCHEMA_VERSION},
+  api_token,
+  metrics_exporter,
+  tauri_commands::AppState,
+};
+
+type Body = UnsyncBoxBody<Bytes, Infallible>;
+
+/// Idle streams are dropped by proxies and some clients; a comment line keeps them open.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// The server currently listening and what it was started with.
+struct Running {
+  port: u16,
+  token: String,
+  shutdown: watch::Sender<bool>,
+  /// Finishes once the listener is closed and the port is free again.
+  task: JoinHandle<()>,
+}
+
+static RUNNING: Mutex<Option<Running>> = Mutex::new(None);
+
+/// Starts, restarts or stops the server to match `api` in the settings.
+/// Runs at startup and on every settings change; a server whose port and
+/// token are unchanged keeps running.
+pub fn apply(app: &AppHandle) {
+  let settings = app.state::<AppState>().settings().map(|settings| settings.api).unwrap_or_default();
+  let token = if settings.enabled {
+    let token = match settings.token.as_deref() {
+      Some(token) => Ok(token.trim().to_string()),
+      None => api_token::load_or_create(),
+    };
+    match token {
+      Ok(token) => Some(token),
+      Err(e) => {
+        println!("❌ Local API disabled: {}", e);
+        None
+      }
+    }
+  } else {
+    None
+  };
+
+  let mut running = RUNNING.lock().unwrap();
+  if let (Some(token), Some(current)) = (&token, running.as_ref()) {
+    if current.port == settings.port && current.token == *token {
+      return;
+    }
+  }
+  let previous = running.take().map(|previous| {
+    let _ = previous.shutdown.send(true);
+    println!("🛑 Local API on port {} stopped", previous.port);
+    previous.task
+  });
+  let Some(token) = token else { return };
+
+  let (shutdown, shutdown_rx) = watch::channel(false);
+  let task = tauri::async_runtime::spawn(serve(app.clone(), settings.port, Arc::from(token.as_str()), shutdown_rx, previous));
+  *running = Some(Running { port: settings.port, token, shutdown, task });
+}
+
+async fn serve(app: AppHandle, port: u16, token: Arc<str>, mut shutdown: watch::Receiver<bool>, previous: Option<JoinHandle<()>>) {
+  // A restart on the same port has to wait for the old listener to close.
+  if let Some(previous) = previous {
+    let _ = previous.await;
+  }
+  let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+  let listener = match TcpListener::bind(address).await {
+    Ok(listener) => listener,
+    Err(e) => {
+      println!("❌ Local API could not listen on {}: {}", address, e);
+      // Forget this server so the next settings change tries again.
+      let mut running = RUNNING.lock().unwrap();
+      if running.as_ref().is_some_and(|current| current.port == port && *current.token == *token) {
+        *running = None;
+      }
+      return;
+    }
+  };
+  println!("🌐 Local API listening on http://{}", address);
+
+  loop {
+    let stream = tokio::select! {
+      accepted = listener.accept() => match accepted {
+        Ok((stream, _)) => stream,
+        Err(e) => {
+          // Errors such as running out of file descriptors persist, so back off instead of spinning.
+          println!("⚠️ Local API accept failed: {}", e);
+          tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+          continue;
+        }
+      },
+      _ = shutdown.changed() => return,
+    };
+    let app = app.clone();
+    let token = token.clone();
+    let mut shutdown = shutdown.clone();
+    tauri::async_runtime::spawn(async move {
+      let stream_shutdown = shutdown.clone();
+      let service = service_fn(move |request| handle(app.clone(), token.clone(), port, stream_shutdown.clone(), request));
+      let connection = http1::Builder::new().serve_connection(TokioIo::new(stream), service);
+      // Dropping the connection closes it, so a stopped server drops its clients too.
+      tokio::select! {
+        result = connection => {
+          if let Err(e) = result {
+            println!("⚠️ Local API connection failed: {}", e);
+          }
+        }
+        _ = shutdown.changed() => {}
+      }
+    });
+  }
+}
+
+async fn handle(
+  app: AppHandle,
+  token: Arc<str>,
+  port: u16,
+  shutdown: watch::Receiver<bool>,
+  request: Request<Incoming>,
+) -> Result<Response<Body>, Infallible> {
+  if request.method() != Method::GET {
+    return Ok(error(StatusCode::METHOD_NOT_ALLOWED, "Only GET is supported"));
+  }
+  if !host_allowed(&request, port) {
+    return Ok(error(StatusCode::FORBIDDEN, "Unexpected Host header"));
+  }
+  if request.uri().path() == "/v1/health" {
+    return Ok(json(StatusCode::OK, &Health {
+      status: "ok".to_string(),
+      app_version: app.package_info().version.to_string(),
+      schema_version: SCHEMA_VERSION,
+    }));
+  }
+  if !authorized(&request, &token) {
+    let mut response = error(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token");
+    response.headers_mut().insert(header::WWW_AUTHENTICATE, header::HeaderValue::from_static("Bearer"));
+    return Ok(response);
+  }
+
+  let state = app.state::<AppState>();
+  let response = match request.uri().path() {
+    "/v1/usage" => match state.usage_snapshot() {
+      Some(snapshot) => data(Ok(snapshot)),
+      None => error(StatusCode::SERVICE_UNAVAILABLE, "Usage has not been polled yet"),
+    },
+    "/v1/summary" => {
+      let period = query_param(&request, "period").unwrap_or_else(|| "day".to_string());
+      if !matches!(period.as_str(), "day" | "week" | "month") {
+        error(StatusCode::BAD_REQUEST, "period must be day, week or month")
+      } else {
+        data(state.cached_summary(&period).await)
+      }
+    }
+    "/v1/blocks" => data(state.cached_blocks().await),
+    "/v1/stream" => match Topic::parse_list(&query_param(&request, "topics").unwrap_or_default()) {
+      Ok(topics) if topics.is_empty() => stream(app.clone(), Topic::ALL.to_vec(), shutdown),
+      Ok(topics) => stream(app.clone(), topics, shutdown),
+      Err(e) => error(StatusCode::BAD_REQUEST, &e),
+    },
+    "/v1/budgets" if state.usage_snapshot().is_none() => error(StatusCode::SERVICE_UNAVAILABLE, "Usage has not been polled yet"),
+    "/v1/budgets" => data(state.budget_statuses().await),
+    "/metrics" if state.settings().is_ok_and(|settings| settings.metrics.endpoint) => match metrics_exporter::render(&state).await {
+      Some(text) => plain(metrics_exporter::CONTENT_TYPE, text),
+      None => error(StatusCode::SERVICE_UNAVAILABLE, "Usage has not been polled yet"),
+    },
+    _ => error(StatusCode::NOT_FOUND, "Unknown endpoint"),
+  };
+  Ok(response)
+}
+
+/// Server-Sent Events for the subscribed topics. The current usage and
+/// active block are sent first, so a client has data before the next poll.
+fn stream(app: AppHandle, topics: Vec<Topic>, mut shutdown: watch::Receiver<bool>) -> Response<Body> {
+  let (sender, receiver) = mpsc::channel::<Bytes>(16);
+  let mut events = app.state::<AppState>().events.subscribe();
+  tauri::async_runtime::spawn(async move {
+    let state = app.state::<AppState>();
+    let mut initial = Vec::new();
+    if topics.contains(&Topic::Usage) {
+      initial.extend(state.usage_snapshot().map(StreamEvent::Usage));
+    }
+    if topics.contains(&Topic::Blocks) {
+      if let Ok(blocks) = state.cached_blocks().await {
+        initial.push(StreamEvent::Blocks(blocks.into_iter().find(|block| block.is_active)));
+      }
+    }
+    for event in initial {
+      if sender.send(sse_frame(event)).await.is_err() {
+        return;
+      }
+    }
+
+    let mut keep_alive = tokio::time::interval(KEEP_ALIVE);
+    loop {
+      let frame = tokio::select! {
+        received = events.recv() => match received {
+          Ok(event) if topics.contains(&event.topic()) => sse_frame(event),
+          Ok(_) | Err(RecvError::Lagged(_)) => continue,
+          Err(RecvError::Closed) => return,
+        },
+        _ = keep_alive.tick() => Bytes::from_static(b": keep-alive\n\n"),
+        _ = shutdown.changed() => return,
+      };
+      // Fails once the client has disconnected.
+      if sender.send(frame).await.is_err() {
+        return;
+      }
+    }
+  });
+
+  let frames = futures_util::stream::unfold(receiver, |mut receiver| async move {
+    receiver.recv().await.map(|bytes| (Ok(Frame::data(bytes)), receiver))
+  });
+  Response::builder()
+    .header(header::CONTENT_TYPE, "text/event-stream")
+    .header(header::CACHE_CONTROL, "no-store")
+    .body(StreamBody::new(frames).boxed_unsync())
+    .unwrap()
+}
+
+fn sse_frame(event: StreamEvent) -> Bytes {
+  let topic = event.topic().name();
+  let json = serde_json::to_string(&StreamMessage::new(event)).unwrap_or_default();
+  Bytes::from(format!("event: {}\ndata: {}\n\n", topic, json))
+}
+
+/// Browsers send the name they resolved, so a page served from a rebound
+/// DNS name cannot reach the API even though the socket is local.
+fn host_allowed(request: &Request<Incoming>, port: u16) -> bool {
+  let Some(host) = request.headers().get(header::HOST).and_then(|host| host.to_str().ok()) else { return true };
+  let name = host.strip_suffix(&format!(":{}", port)).unwrap_or(host);
+  matches!(name, "127.0.0.1" | "localhost" | "[::1]")
+}
+
+/// The bearer token, or for `/v1/stream` alone a `token` query parameter,
+/// since browsers cannot set headers on `EventSource`.
+fn authorized(request: &Request<Incoming>, token: &str) -> bool {
+  let header = request.headers().get(header::AUTHORIZATION)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.strip_prefix("Bearer "))
+    .map(|presented| presented.trim().to_string());
+  let query = || (request.uri().path() == "/v1/stream").then(|| query_param(request, "token")).flatten();
+  header.or_else(query).is_some_and(|presented| constant_time_eq(presented.as_bytes(), token.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+  a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn query_param(request: &Request<Incoming>, name: &str) -> Option<String> {
+  request.uri().query()?
+    .split('&')
+    .filter_map(|pair| pair.split_once('='))
+    .find(|(key, _)| *key == name)
+    .map(|(_, value)| percent_decode(value))
+}
+
+fn percent_decode(value: &str) -> String {
+  let bytes = value.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    let hex = bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok()).and_then(|hex| u8::from_str_radix(hex, 16).ok());
+    match (bytes[i], hex) {
+      (b'%', Some(byte)) => {
+        decoded.push(byte);
+        i += 3;
+      }
+      (b'+', _) => {
+        decoded.push(b' ');
+        i += 1;
+      }
+      (byte, _) => {
+        decoded.push(byte);
+        i += 1;
+      }
+    }
+  }
+  String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn data<T: Serialize>(result: Result<T, String>) -> Response<Body> {
+  match result {
+    Ok(value) => json(StatusCode::OK, &Envelope::new(value)),
+    Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e.lines().next().unwrap_or_default()),
+  }
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Body> {
+  json(status, &ErrorBody::new(message))
+}
+
+fn plain(content_type: &'static str, text: String) -> Response<Body> {
+  Response::builder()
+    .header(header::CONTENT_TYPE, content_type)
+    .header(header::CACHE_CONTROL, "no-store")
+    .body(Full::new(Bytes::from(text)).boxed_unsync())
+    .unwrap()
+}
+
+fn json<T: Serialize>(status: StatusCode, body: &T) -> Response<Body> {
+  let body = serde_json::to_vec(body).unwrap_or_default();
+  Response::builder()
+    .status(status)
+    .header(header::CONTENT_TYPE, "application/json")
+    .header(header::CACHE_CONTROL, "no-store")
+    .body(Full::new(Bytes::from(body)).boxed_unsync())
+    .unwrap()
+}
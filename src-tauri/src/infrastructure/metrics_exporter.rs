@@ -0,0 +1,37 @@
+use chrono::{Local, Utc};
+use crate::application::{budgets, metrics::{self, MetricsInput}};
+use super::{atomic_file, tauri_commands::AppState};
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// The latest poll in the Prometheus text format, or `None` before the first poll.
+/// Everything beyond the poll's stats comes from the one cached daily report,
+/// so a write after every poll does not run ccusage again each time.
+pub async fn render(state: &AppState) -> Option<String> {
+  let snapshot = state.usage_snapshot()?;
+  let daily = state.cached_daily().await.unwrap_or_default();
+  let today = Local::now().date_naive();
+  let period_costs = metrics::period_costs(&daily, today);
+  let budgets = state.settings().ok()
+    .map(|settings| budgets::budget_statuses(&settings.profile().notifications.rules, &snapshot.stats, &period_costs))
+    .unwrap_or_default();
+  let today = today.format("%Y-%m-%d").to_string();
+  let input = MetricsInput {
+    today: daily.into_iter().find(|day| day.date == today),
+    week_cost: period_costs.weekly_cost,
+    month_cost: period_costs.monthly_cost,
+    snapshot,
+    budgets,
+  };
+  Some(metrics::render(&input, Utc::now()))
+}
+
+/// Rewrites `metrics.textfile` after a poll, if one is configured.
+pub async fn write_textfile(state: &AppState) {
+  let Some(path) = state.settings().ok().and_then(|settings| settings.metrics.expanded_textfile()) else { return };
+  let Some(text) = render(state).await else { return };
+  // The collector may read at any moment, so the file is replaced, not rewritten.
+  if let Err(e) = atomic_file::write(&path, &text) {
+    println!("⚠️ Failed to write metrics textfile: {}", e);
+  }
+}
@@ -0,0 +1,125 @@
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+use super::{placement, tauri_commands::AppState, tray, window_state_store::SavedGeometry};
+
+pub const MINI_LABEL: &str = "mini";
+const DEFAULT_WIDTH: f64 = 240.0;
+const DEFAULT_HEIGHT: f64 = 96.0;
+const MARGIN: f64 = 16.0;
+
+pub fn is_open(app: &AppHandle) -> bool {
+  app.get_webview_window(MINI_LABEL).is_some()
+}
+
+/// Opens or closes the mini widget, returning whether it is now open.
+pub fn toggle(app: &AppHandle) -> Result<bool, String> {
+  if is_open(app) {
+    close(app)?;
+    Ok(false)
+  } else {
+    open(app)?;
+    Ok(true)
+  }
+}
+
+/// Reopens the widget at launch if it was open when the app last exited.
+pub fn restore(app: &AppHandle) {
+  let was_open = app.state::<AppState>().window_state.lock().unwrap().mini_open;
+  if was_open {
+    if let Err(e) = open(app) {
+      println!("⚠️ Failed to restore mini widget: {}", e);
+    }
+  }
+}
+
+/// The widget is created fresh each time instead of shown again: a newly
+/// built unfocused window never takes focus, while `show()` may activate it.
+pub fn open(app: &AppHandle) -> Result<(), String> {
+  if is_open(app) {
+    return Ok(());
+  }
+  let geometry = saved_geometry(app).unwrap_or_else(|| default_geometry(app));
+  let window = WebviewWindowBuilder::new(app, MINI_LABEL, WebviewUrl::App("mini".into()))
+    .title("Claude Token Monitor")
+    .inner_size(geometry.width, geometry.height)
+    .min_inner_size(160.0, 64.0)
+    .position(geometry.x, geometry.y)
+    .decorations(false)
+    .transparent(true)
+    .shadow(false)
+    .resizable(true)
+    .always_on_top(true)
+    .visible_on_all_workspaces(true)
+    .skip_taskbar(true)
+    .focused(false)
+    .build()
+    .map_err(|e| format!("Failed to create mini widget: {}", e))?;
+
+  let window_clone = window.clone();
+  window.on_window_event(move |event| {
+    if matches!(event, WindowEvent::Moved(_) | WindowEvent::Resized(_)) {
+      record_geometry(&window_clone);
+    }
+  });
+  set_open(app, true);
+  println!("🪟 Mini widget opened");
+  Ok(())
+}
+
+pub fn close(app: &AppHandle) -> Result<(), String> {
+  if let Some(window) = app.get_webview_window(MINI_LABEL) {
+    window.close().map_err(|e| e.to_string())?;
+  }
+  set_open(app, false);
+  Ok(())
+}
+
+fn set_open(app: &AppHandle, open: bool) {
+  app.state::<AppState>().window_state.lock().unwrap().mini_open = open;
+  placement::schedule_save(app);
+  tray::update_usage(app);
+}
+
+fn record_geometry(window: &WebviewWindow) {
+  let (Ok(scale), Ok(position), Ok(size)) = (window.scale_factor(), window.outer_position(), window.inner_size()) else {
+    return;
+  };
+  let position: LogicalPosition<f64> = position.to_logical(scale);
+  let size: LogicalSize<f64> = size.to_logical(scale);
+  let app = window.app_handle();
+  app.state::<AppState>().window_state.lock().unwrap().mini = Some(SavedGeometry {
+    x: position.x,
+    y: position.y,
+    width: size.width,
+    height: size.height,
+  });
+  placement::schedule_save(app);
+}
+
+/// The saved geometry, unless its monitor is no longer connected.
+fn saved_geometry(app: &AppHandle) -> Option<SavedGeometry> {
+  let saved = app.state::<AppState>().window_state.lock().unwrap().mini?;
+  let monitors = app.available_monitors().ok()?;
+  monitors.iter()
+    .any(|monitor| {
+      let scale = monitor.scale_factor();
+      let position: LogicalPosition<f64> = monitor.position().to_logical(scale);
+      let size: LogicalSize<f64> = monitor.size().to_logical(scale);
+      saved.x >= position.x && saved.x < position.x + size.width
+        && saved.y >= position.y && saved.y < position.y + size.height
+    })
+    .then_some(saved)
+}
+
+/// Top-right corner of the primary monitor's work area.
+fn default_geometry(app: &AppHandle) -> SavedGeometry {
+  let (x, y) = app.primary_monitor().ok().flatten()
+    .map(|monitor| {
+      let scale = monitor.scale_factor();
+      let area = monitor.work_area();
+      let position: LogicalPosition<f64> = area.position.to_logical(scale);
+      let size: LogicalSize<f64> = area.size.to_logical(scale);
+      (position.x + size.width - DEFAULT_WIDTH - MARGIN, position.y + MARGIN)
+    })
+    .unwrap_or((MARGIN, MARGIN));
+  SavedGeometry { x, y, width: DEFAULT_WIDTH, height: DEFAULT_HEIGHT }
+}
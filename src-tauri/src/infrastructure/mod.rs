@@ -1,3 +1,40 @@
+pub mod api_schema;
+pub mod api_token;
+pub mod atomic_file;
 pub mod ccusage_repository;
+#[cfg(feature = "gui")]
+pub mod dashboard_window;
+pub mod digest_log;
+pub mod event_stream;
+#[cfg(feature = "gui")]
+pub mod local_api;
+#[cfg(feature = "gui")]
+pub mod metrics_exporter;
+#[cfg(feature = "gui")]
+pub mod mini_window;
+#[cfg(feature = "gui")]
+pub mod placement;
+pub mod policy_store;
+pub mod settings_store;
+#[cfg(feature = "gui")]
+pub mod settings_watcher;
+#[cfg(feature = "gui")]
+pub mod shortcut;
+pub mod smtp_notifier;
+pub mod snapshot_cache;
+#[cfg(feature = "gui")]
+pub mod sound_player;
+#[cfg(feature = "gui")]
 pub mod tauri_commands;
+#[cfg(feature = "gui")]
 pub mod tray;
+#[cfg(feature = "gui")]
+pub mod tray_icon;
+#[cfg(feature = "gui")]
+pub mod tray_menu;
+#[cfg(feature = "gui")]
+pub mod usage_monitor;
+pub mod webhook_notifier;
+#[cfg(feature = "gui")]
+pub mod window_manager;
+pub mod window_state_store;
\ No newline at end of file
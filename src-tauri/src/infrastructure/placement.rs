@@ -0,0 +1,223 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, Position, Rect, Size, WebviewWindow};
+use crate::domain::settings::{PopupAnchor, WindowSettings};
+use super::{tauri_commands::AppState, window_state_store::SavedPosition};
+
+/// Gap between the popup and the tray icon, cursor or work-area edge, in logical pixels.
+const MARGIN: f64 = 8.0;
+/// Moves this close to where the popup was placed are our own, not a drag.
+const OWN_MOVE_TOLERANCE: i32 = 2;
+/// Drags emit a stream of moves; persist once the window has settled.
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// What triggered showing the popup.
+#[derive(Debug, Clone)]
+pub enum Origin {
+  /// A tray click; the rect is empty on platforms that do not report it.
+  Tray(Rect),
+  /// A keyboard shortcut: open at the pointer.
+  Cursor,
+  /// A command or menu action with no location of its own.
+  Default,
+}
+
+/// The last position the popup was placed at, to tell our moves from drags.
+static PLACED_AT: Mutex<Option<PhysicalPosition<i32>>> = Mutex::new(None);
+static SAVE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy)]
+struct Area {
+  x: f64,
+  y: f64,
+  width: f64,
+  height: f64,
+}
+
+impl Area {
+  fn work_area(monitor: &Monitor) -> Self {
+    let area = monitor.work_area();
+    Self {
+      x: area.position.x as f64,
+      y: area.position.y as f64,
+      width: area.size.width as f64,
+      height: area.size.height as f64,
+    }
+  }
+
+  fn bounds(monitor: &Monitor) -> Self {
+    Self {
+      x: monitor.position().x as f64,
+      y: monitor.position().y as f64,
+      width: monitor.size().width as f64,
+      height: monitor.size().height as f64,
+    }
+  }
+
+  fn center(&self) -> (f64, f64) {
+    (self.x + self.width / 2.0, self.y + self.height / 2.0)
+  }
+
+  fn contains(&self, (x, y): (f64, f64)) -> bool {
+    x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+  }
+
+  /// Moves a `width` x `height` box at (x, y) fully inside the area, keeping the
+  /// top-left corner visible when the box is larger than the area.
+  fn clamp(&self, (x, y): (f64, f64), (width, height): (f64, f64)) -> (f64, f64) {
+    let x = x.min(self.x + self.width - width).max(self.x);
+    let y = y.min(self.y + self.height - height).max(self.y);
+    (x, y)
+  }
+}
+
+/// Sizes and positions the popup for `origin` and the window settings.
+pub fn place_popup(window: &WebviewWindow, settings: &WindowSettings, origin: Origin) -> Result<(), String> {
+  let app = window.app_handle();
+  let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+  let cursor = app.cursor_position().ok().map(|position| (position.x, position.y));
+  let tray = match &origin {
+    Origin::Tray(rect) => tray_rect(rect, &monitors),
+    _ => None,
+  };
+
+  // A shortcut always opens at the pointer, even if the popup was dragged elsewhere.
+  let saved = (settings.remember_position && !matches!(origin, Origin::Cursor))
+    .then(|| saved_position(app, &monitors))
+    .flatten();
+  let point = saved.map(|saved| (saved.x as f64, saved.y as f64))
+    .or_else(|| tray.map(|tray| tray.center()))
+    .or(cursor);
+  let monitor = point
+    .and_then(|point| monitors.iter().find(|monitor| Area::bounds(monitor).contains(point)).cloned())
+    .or_else(|| window.primary_monitor().ok().flatten())
+    .or_else(|| monitors.first().cloned())
+    .ok_or("No monitor found")?;
+
+  let scale = monitor.scale_factor();
+  let size = (settings.width * scale, settings.height * scale);
+  let margin = MARGIN * scale;
+  let area = Area::work_area(&monitor);
+
+  let anchor = match origin {
+    Origin::Cursor => PopupAnchor::NearCursor,
+    _ => settings.anchor,
+  };
+  let position = match (saved, anchor, tray, cursor) {
+    (Some(saved), _, _, _) => (saved.x as f64, saved.y as f64),
+    (_, PopupAnchor::Tray, Some(tray), _) => {
+      let x = tray.x + tray.width / 2.0 - size.0 / 2.0;
+      let below = tray.y + tray.height + margin;
+      // A taskbar at the bottom of the screen opens the popup upwards.
+      let y = if below + size.1 > area.y + area.height { tray.y - size.1 - margin } else { below };
+      (x, y)
+    }
+    (_, PopupAnchor::Tray | PopupAnchor::NearCursor, _, Some((x, y))) => {
+      let below = y + margin;
+      let y = if below + size.1 > area.y + area.height { y - size.1 - margin } else { below };
+      (x - size.0 / 2.0, y)
+    }
+    (_, PopupAnchor::BottomRight, _, _) => (area.x + area.width - size.0 - margin, area.y + area.height - size.1 - margin),
+    _ => (area.x + area.width - size.0 - margin, area.y + margin),
+  };
+  let (x, y) = area.clamp(position, size);
+
+  let position = PhysicalPosition::new(x.round() as i32, y.round() as i32);
+  *PLACED_AT.lock().unwrap() = Some(position);
+  window.set_size(Size::Logical(tauri::LogicalSize { width: settings.width, height: settings.height })).map_err(|e| e.to_string())?;
+  window.set_position(Position::Physical(position)).map_err(|e| e.to_string())
+}
+
+/// Remembers a position the user dragged the popup to, for this monitor setup.
+pub fn record_move(window: &WebviewWindow, position: PhysicalPosition<i32>) {
+  let placed = *PLACED_AT.lock().unwrap();
+  let own_move = placed.is_some_and(|placed| {
+    (placed.x - position.x).abs() <= OWN_MOVE_TOLERANCE && (placed.y - position.y).abs() <= OWN_MOVE_TOLERANCE
+  });
+  if own_move || !window.is_visible().unwrap_or(false) {
+    return;
+  }
+  let app = window.app_handle();
+  let state = app.state::<AppState>();
+  if !state.settings().is_ok_and(|settings| settings.window.remember_position) {
+    return;
+  }
+  let Ok(monitors) = window.available_monitors() else { return };
+
+  state.window_state.lock().unwrap()
+    .popup_positions
+    .insert(monitor_setup_key(&monitors), SavedPosition { x: position.x, y: position.y });
+  *PLACED_AT.lock().unwrap() = Some(position);
+  schedule_save(app);
+}
+
+/// Forgets the dragged position for the current monitor setup.
+pub fn reset_position(app: &AppHandle) -> Result<(), String> {
+  let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+  let state = app.state::<AppState>();
+  let removed = state.window_state.lock().unwrap().popup_positions.remove(&monitor_setup_key(&monitors));
+  if removed.is_some() {
+    schedule_save(app);
+  }
+  Ok(())
+}
+
+/// Identifies a monitor arrangement, so a laptop on its own and docked to an
+/// external display each keep their own popup position.
+pub fn monitor_setup_key(monitors: &[Monitor]) -> String {
+  let mut parts: Vec<String> = monitors.iter()
+    .map(|monitor| format!(
+      "{}x{}+{}+{}@{}",
+      monitor.size().width, monitor.size().height, monitor.position().x, monitor.position().y, monitor.scale_factor()
+    ))
+    .collect();
+  parts.sort();
+  parts.join(";")
+}
+
+fn saved_position(app: &AppHandle, monitors: &[Monitor]) -> Option<SavedPosition> {
+  let state = app.state::<AppState>();
+  let saved = state.window_state.lock().unwrap().popup_positions.get(&monitor_setup_key(monitors)).copied()?;
+  // Ignore positions whose monitor has since been rearranged away.
+  monitors.iter()
+    .any(|monitor| Area::work_area(monitor).contains((saved.x as f64, saved.y as f64)))
+    .then_some(saved)
+}
+
+/// The tray icon rect in physical pixels, or `None` when the platform left it empty.
+/// A logical rect is scaled by the monitor it lands on, so a tray on a secondary
+/// display with a different scale factor is still found in the right place.
+fn tray_rect(rect: &Rect, monitors: &[Monitor]) -> Option<Area> {
+  let to_physical = |scale: f64| {
+    let position = rect.position.to_physical::<f64>(scale);
+    let size = rect.size.to_physical::<f64>(scale);
+    Area { x: position.x, y: position.y, width: size.width, height: size.height }
+  };
+  let tray = monitors.iter()
+    .map(|monitor| (Area::bounds(monitor), to_physical(monitor.scale_factor())))
+    .find(|(bounds, tray)| bounds.contains(tray.center()))
+    .map(|(_, tray)| tray)
+    .unwrap_or_else(|| to_physical(monitors.first().map_or(1.0, Monitor::scale_factor)));
+  if tray.x == 0.0 && tray.y == 0.0 && tray.width == 0.0 && tray.height == 0.0 {
+    return None;
+  }
+  Some(tray)
+}
+
+/// Persists `AppState::window_state` once it stops changing.
+pub fn schedule_save(app: &AppHandle) {
+  let generation = SAVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    tokio::time::sleep(SAVE_DELAY).await;
+    if SAVE_GENERATION.load(Ordering::SeqCst) != generation {
+      return;
+    }
+    let state = app.state::<AppState>();
+    let window_state = state.window_state.lock().unwrap().clone();
+    if let Err(e) = state.window_state_store.save(&window_state) {
+      println!("⚠️ Failed to save window state: {}", e);
+    }
+  });
+}
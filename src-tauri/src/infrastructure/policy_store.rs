@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::domain::policy::{Policy, PolicyStatus};
+
+/// Overrides the system-wide policy location, mainly for testing a policy.
+const POLICY_ENV: &str = "CLAUDE_TOKEN_MONITOR_POLICY";
+
+/// The loaded organization policy and where it came from.
+#[derive(Default)]
+pub struct LoadedPolicy {
+  pub policy: Option<Policy>,
+  pub source: Option<PathBuf>,
+  pub error: Option<String>,
+}
+
+impl LoadedPolicy {
+  pub fn status(&self) -> PolicyStatus {
+    PolicyStatus {
+      source: self.source.as_ref().map(|path| path.display().to_string()),
+      error: self.error.clone(),
+      locked: self.policy.as_ref().map(Policy::locked_settings).unwrap_or_default(),
+    }
+  }
+}
+
+pub fn policy_path() -> PathBuf {
+  if let Ok(path) = std::env::var(POLICY_ENV) {
+    return PathBuf::from(path);
+  }
+  #[cfg(target_os = "macos")]
+  {
+    PathBuf::from("/Library/Application Support/claude-token-monitor/policy.toml")
+  }
+  #[cfg(target_os = "windows")]
+  {
+    let program_data = std::env::var("ProgramData").unwrap_or_else(|_| "C:\\ProgramData".to_string());
+    PathBuf::from(program_data).join("claude-token-monitor").join("policy.toml")
+  }
+  #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+  {
+    PathBuf::from("/etc/claude-token-monitor/policy.toml")
+  }
+}
+
+/// Loads the policy without logging, so the CLI keeps its output clean;
+/// callers report `source` and `error` themselves.
+pub fn load_policy() -> LoadedPolicy {
+  let path = policy_path();
+  if !path.exists() {
+    return LoadedPolicy::default();
+  }
+
+  let result = fs::read_to_string(&path)
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    .and_then(|contents| toml::from_str::<Policy>(&contents).map_err(|e| format!("Invalid policy {}: {}", path.display(), e)))
+    .and_then(|policy| policy.validate().map(|_| policy));
+
+  match result {
+    Ok(policy) => LoadedPolicy { policy: Some(policy), source: Some(path), error: None },
+    Err(e) => LoadedPolicy { policy: None, source: Some(path), error: Some(e) },
+  }
+}
@@ -0,0 +1,173 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde_json::{Map, Value};
+use super::atomic_file;
+use crate::domain::settings::{AppSettings, DEFAULT_PROFILE, SETTINGS_VERSION};
+
+/// Matches the bundle identifier so the file lives next to Tauri's own app config.
+pub const APP_IDENTIFIER: &str = "com.kimdj2.claude-token-monitor";
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Migrations indexed by the version they upgrade from: `MIGRATIONS[0]`
+/// turns a version 0 document into version 1, and so on.
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v0_to_v1, migrate_v1_to_v2];
+
+pub struct SettingsStore {
+  path: PathBuf,
+}
+
+impl SettingsStore {
+  pub fn new(path: PathBuf) -> Self {
+    Self { path }
+  }
+
+  pub fn default_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir().ok_or("Could not determine the platform config directory")?;
+    Ok(config_dir.join(APP_IDENTIFIER).join(SETTINGS_FILE))
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Loads settings, returning defaults when the file does not exist yet.
+  pub fn load(&self) -> Result<AppSettings, String> {
+    if !self.path.exists() {
+      return Ok(AppSettings::default());
+    }
+    let contents = fs::read_to_string(&self.path)
+      .map_err(|e| format!("Failed to read {}: {}", self.path.display(), e))?;
+    parse_settings(&contents).map_err(|e| format!("{}: {}", self.path.display(), e))
+  }
+
+  pub fn save(&self, settings: &AppSettings) -> Result<(), String> {
+    self.back_up_invalid()?;
+    let json = serde_json::to_string_pretty(settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    atomic_file::write(&self.path, &json)
+  }
+
+  /// Where a file that failed to load is kept when it is replaced.
+  pub fn backup_path(&self) -> PathBuf {
+    self.path.with_extension("json.invalid")
+  }
+
+  /// A file that does not load is most likely a hand edit gone wrong; keep a
+  /// copy rather than silently replacing the user's work with defaults.
+  fn back_up_invalid(&self) -> Result<(), String> {
+    let Ok(contents) = fs::read_to_string(&self.path) else { return Ok(()) };
+    if parse_settings(&contents).is_ok() {
+      return Ok(());
+    }
+    let backup_path = self.backup_path();
+    fs::write(&backup_path, contents).map_err(|e| format!("Failed to back up {} to {}: {}", self.path.display(), backup_path.display(), e))?;
+    println!("💾 Kept the invalid settings file as {}", backup_path.display());
+    Ok(())
+  }
+}
+
+pub fn parse_settings(contents: &str) -> Result<AppSettings, String> {
+  let mut value: Value = serde_json::from_str(contents).map_err(|e| format!("invalid JSON: {}", e))?;
+  migrate(&mut value)?;
+  let settings: AppSettings = serde_json::from_value(value).map_err(|e| format!("invalid settings: {}", e))?;
+  settings.validate()?;
+  Ok(settings)
+}
+
+fn migrate(value: &mut Value) -> Result<(), String> {
+  let mut version = value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+  if version > SETTINGS_VERSION {
+    return Err(format!(
+      "settings version {} is newer than this app supports ({})",
+      version, SETTINGS_VERSION
+    ));
+  }
+  while version < SETTINGS_VERSION {
+    MIGRATIONS[version as usize](value);
+    version += 1;
+    if let Value::Object(map) = value {
+      map.insert("version".to_string(), Value::from(version));
+    }
+  }
+  Ok(())
+}
+
+// Unversioned files predate the schema; their fields already match version 1.
+fn migrate_v0_to_v1(_value: &mut Value) {}
+
+// Version 2 moved the per-account fields into named profiles.
+fn migrate_v1_to_v2(value: &mut Value) {
+  let Value::Object(map) = value else { return };
+  let mut profile = Map::new();
+  for field in ["data_roots", "plan", "notifications"] {
+    if let Some(field_value) = map.remove(field) {
+      profile.insert(field.to_string(), field_value);
+    }
+  }
+  let mut profiles = Map::new();
+  profiles.insert(DEFAULT_PROFILE.to_string(), Value::Object(profile));
+  map.insert("profiles".to_string(), Value::Object(profiles));
+  map.insert("active_profile".to_string(), Value::from(DEFAULT_PROFILE));
+}
+
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+  use crate::domain::entities::Plan;
+  use super::*;
+
+  fn data_root() -> String {
+    std::env::temp_dir().display().to_string()
+  }
+
+  /// A file from before versioning, with the account fields at the top level.
+  fn unversioned() -> Value {
+    json!({
+      "refresh_interval_secs": 45,
+      // Data roots must exist to validate.
+      "data_roots": [data_root()],
+      "plan": { "plan": "max5x", "block_token_limit": 220000 },
+      "notifications": { "webhooks": [{ "name": "team", "url": "https://example.com/hook" }] }
+    })
+  }
+
+  #[test]
+  fn migrates_an_unversioned_file_into_the_default_profile() {
+    let settings = parse_settings(&unversioned().to_string()).unwrap();
+    assert_eq!(settings.version, SETTINGS_VERSION);
+    assert_eq!(settings.refresh_interval_secs, 45);
+    assert_eq!(settings.active_profile, DEFAULT_PROFILE);
+    assert_eq!(settings.profiles.len(), 1);
+    let profile = &settings.profiles[DEFAULT_PROFILE];
+    assert_eq!(profile.data_roots, vec![data_root()]);
+    assert_eq!(profile.plan.plan, Plan::Subscription);
+    assert_eq!(profile.plan.block_token_limit, Some(220_000));
+    assert_eq!(profile.notifications.webhooks[0].name, "team");
+  }
+
+  #[test]
+  fn migrates_a_version_1_file_the_same_way() {
+    let mut v1 = unversioned();
+    v1["version"] = json!(1);
+    let mut v0 = unversioned();
+    migrate(&mut v0).unwrap();
+    migrate(&mut v1).unwrap();
+    assert_eq!(v0, v1);
+    assert_eq!(v1["version"], json!(SETTINGS_VERSION));
+    assert!(v1.get("data_roots").is_none() && v1.get("plan").is_none() && v1.get("notifications").is_none());
+  }
+
+  #[test]
+  fn leaves_a_current_file_alone() {
+    let current = serde_json::to_value(AppSettings::default()).unwrap();
+    let mut migrated = current.clone();
+    migrate(&mut migrated).unwrap();
+    assert_eq!(migrated, current);
+  }
+
+  #[test]
+  fn rejects_a_file_from_a_newer_version() {
+    let error = parse_settings(&json!({ "version": SETTINGS_VERSION + 1 }).to_string()).unwrap_err();
+    assert!(error.contains("newer than this app supports"), "{}", error);
+  }
+}
@@ -0,0 +1,80 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+use notify::{Event, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use super::tauri_commands::{self, AppState};
+
+/// Editors and dotfile managers often write a file in several steps.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Serialize, Clone)]
+struct SettingsDiagnostic {
+  path: String,
+  error: String,
+}
+
+/// Watches the settings file and applies external edits live. Invalid
+/// edits are reported through a `settings-error` event and ignored, so the
+/// last good configuration stays active.
+pub fn start(app: AppHandle, path: PathBuf) -> Result<(), String> {
+  let dir = path.parent().ok_or("Settings path has no parent directory")?.to_path_buf();
+  std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+  let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+  let mut watcher = notify::recommended_watcher(tx).map_err(|e| format!("Failed to create file watcher: {}", e))?;
+  // Watch the directory: atomic saves replace the file instead of modifying it.
+  watcher.watch(&dir, RecursiveMode::NonRecursive)
+    .map_err(|e| format!("Failed to watch {}: {}", dir.display(), e))?;
+
+  std::thread::spawn(move || {
+    let _watcher = watcher;
+    while let Ok(event) = rx.recv() {
+      if !touches_settings(&event, &path) {
+        continue;
+      }
+      while rx.recv_timeout(DEBOUNCE).is_ok() {}
+      reload(&app, &path);
+    }
+  });
+  Ok(())
+}
+
+fn touches_settings(event: &notify::Result<Event>, path: &Path) -> bool {
+  match event {
+    Ok(event) => (event.kind.is_create() || event.kind.is_modify())
+      && event.paths.iter().any(|changed| changed.file_name() == path.file_name()),
+    Err(_) => false,
+  }
+}
+
+fn reload(app: &AppHandle, path: &Path) {
+  if !path.exists() {
+    return;
+  }
+  let state = app.state::<AppState>();
+  let result = state.settings_store.load()
+    .and_then(|settings| tauri_commands::validate_settings(&settings).map(|_| settings));
+
+  match result {
+    Ok(settings) => {
+      let current = state.user_settings().ok().and_then(|current| serde_json::to_value(current).ok());
+      // Our own saves come back through the watcher as well.
+      if current == serde_json::to_value(&settings).ok() {
+        return;
+      }
+      println!("🔄 Settings file changed, applying");
+      if let Err(e) = state.install_settings(app, settings) {
+        println!("❌ Failed to apply reloaded settings: {}", e);
+      }
+    }
+    Err(e) => {
+      println!("⚠️ Ignoring invalid settings edit: {}", e);
+      let _ = app.emit("settings-error", SettingsDiagnostic {
+        path: path.display().to_string(),
+        error: e,
+      });
+    }
+  }
+}
@@ -0,0 +1,93 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use crate::domain::{entities::ShortcutStatus, settings::ShortcutSettings};
+use super::{placement::Origin, tauri_commands::AppState, window_manager};
+
+/// The shortcut currently registered with the OS.
+static REGISTERED: Mutex<Option<Shortcut>> = Mutex::new(None);
+
+pub fn parse_accelerator(accelerator: &str) -> Result<Shortcut, String> {
+  accelerator.parse::<Shortcut>().map_err(|e| format!("invalid accelerator '{}': {}", accelerator, e))
+}
+
+/// Handler for the global shortcut plugin: toggles the popup at the pointer.
+pub fn handle(app: &AppHandle, _shortcut: &Shortcut, event: ShortcutEvent) {
+  if event.state() != ShortcutState::Pressed {
+    return;
+  }
+  if let Err(e) = window_manager::toggle(app, Origin::Cursor) {
+    println!("❌ Failed to toggle popup from shortcut: {}", e);
+  }
+}
+
+/// Registers the configured shortcut in place of the previous one. Runs at
+/// startup and on every settings change; an unchanged shortcut that is
+/// already registered is left alone, while a failed one is retried, e.g.
+/// once the application holding it has quit.
+pub fn apply(app: &AppHandle) {
+  let state = app.state::<AppState>();
+  let settings = state.settings().map(|settings| settings.shortcut).unwrap_or_default();
+  let wanted = wanted_shortcut(&settings);
+
+  // Decide under the lock but talk to the plugin outside it: registering
+  // dispatches to the main thread, which may itself be waiting in `apply`.
+  // The wanted shortcut is claimed up front so a concurrent `apply` sees it.
+  let previous = {
+    let mut registered = REGISTERED.lock().unwrap();
+    if let (Some(Ok(shortcut)), Some(current)) = (&wanted, *registered) {
+      if *shortcut == current {
+        return;
+      }
+    }
+    let previous = registered.take();
+    if let Some(Ok(shortcut)) = &wanted {
+      *registered = Some(*shortcut);
+    }
+    previous
+  };
+  if let Some(previous) = previous {
+    if let Err(e) = app.global_shortcut().unregister(previous) {
+      println!("⚠️ Failed to unregister previous shortcut: {}", e);
+    }
+  }
+
+  let status = match wanted {
+    None => ShortcutStatus::default(),
+    Some(Err(e)) => ShortcutStatus { accelerator: Some(settings.accelerator.clone()), registered: false, error: Some(e) },
+    Some(Ok(shortcut)) => match app.global_shortcut().register(shortcut) {
+      Ok(()) => {
+        println!("⌨️ Global shortcut {} registered", settings.accelerator);
+        ShortcutStatus { accelerator: Some(settings.accelerator.clone()), registered: true, error: None }
+      }
+      Err(e) => {
+        // Release the claim so the next settings change retries.
+        let mut registered = REGISTERED.lock().unwrap();
+        if *registered == Some(shortcut) {
+          *registered = None;
+        }
+        drop(registered);
+        let error = describe_error(&settings.accelerator, &e.to_string());
+        println!("⚠️ {}", error);
+        ShortcutStatus { accelerator: Some(settings.accelerator.clone()), registered: false, error: Some(error) }
+      }
+    },
+  };
+
+  *state.shortcut_status.lock().unwrap() = status.clone();
+  let _ = app.emit("shortcut-status", status);
+}
+
+fn wanted_shortcut(settings: &ShortcutSettings) -> Option<Result<Shortcut, String>> {
+  let accelerator = settings.accelerator.trim();
+  (settings.enabled && !accelerator.is_empty()).then(|| parse_accelerator(accelerator))
+}
+
+fn describe_error(accelerator: &str, error: &str) -> String {
+  let lower = error.to_lowercase();
+  if lower.contains("already") || lower.contains("in use") {
+    format!("Shortcut {} is already taken by another application or a system shortcut; choose a different one ({})", accelerator, error)
+  } else {
+    format!("Failed to register shortcut {}: {}", accelerator, error)
+  }
+}
@@ -0,0 +1,170 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use lettre::{
+  message::{header::ContentType, Mailbox},
+  transport::smtp::authentication::Credentials,
+  AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use crate::domain::{
+  entities::{Alert, SmtpConfig, SmtpSecurity},
+  notifier::Notifier,
+  settings::CurrencyConfig,
+};
+
+pub struct SmtpNotifier {
+  config: SmtpConfig,
+  /// Costs in alert emails are shown in the profile's currency.
+  currency: CurrencyConfig,
+  transport: AsyncSmtpTransport<Tokio1Executor>,
+  from: Mailbox,
+  to: Vec<Mailbox>,
+}
+
+impl SmtpNotifier {
+  pub fn new(config: SmtpConfig, currency: CurrencyConfig) -> Result<Self, String> {
+    if config.host.trim().is_empty() {
+      return Err("SMTP host is not set".to_string());
+    }
+    let from: Mailbox = config.from.parse()
+      .map_err(|e| format!("Invalid sender address '{}': {}", config.from, e))?;
+    let to = config.to.iter()
+      .map(|address| address.parse::<Mailbox>().map_err(|e| format!("Invalid recipient address '{}': {}", address, e)))
+      .collect::<Result<Vec<_>, _>>()?;
+    if to.is_empty() {
+      return Err("SMTP channel has no recipients".to_string());
+    }
+
+    let builder = match config.security {
+      SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+        .map_err(|e| format!("Invalid SMTP host '{}': {}", config.host, e))?,
+      SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+        .map_err(|e| format!("Invalid SMTP host '{}': {}", config.host, e))?,
+      // Plain connections are only meant for local sinks and relays.
+      SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+    };
+    let mut builder = builder
+      .port(config.port)
+      .timeout(Some(Duration::from_secs(config.timeout_secs.max(1))));
+    if let (Some(username), Some(password)) = (&config.username, &config.password) {
+      builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+
+    Ok(Self { transport: builder.build(), config, currency, from, to })
+  }
+
+  pub async fn send(&self, subject: &str, body: &str) -> Result<(), String> {
+    let mut message = Message::builder()
+      .from(self.from.clone())
+      .subject(subject)
+      .header(ContentType::TEXT_PLAIN);
+    for recipient in &self.to {
+      message = message.to(recipient.clone());
+    }
+    let message = message.body(body.to_string())
+      .map_err(|e| format!("Failed to build email: {}", e))?;
+
+    self.transport.send(message).await
+      .map_err(|e| format!("SMTP delivery via {}:{} failed: {}", self.config.host, self.config.port, e))?;
+    println!("✅ Email sent to {} recipient(s): {}", self.to.len(), subject);
+    Ok(())
+  }
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+  fn name(&self) -> String {
+    format!("smtp:{}", self.config.host)
+  }
+
+  async fn notify(&self, alert: &Alert) -> Result<(), String> {
+    if !self.config.enabled || !self.config.send_alerts || alert.severity < self.config.min_severity {
+      return Ok(());
+    }
+
+    let stats = &alert.stats;
+    let subject = format!("[Claude Token Monitor] {}", alert.title);
+    let body = format!(
+      "{}\n\nTriggered by {} = {:.2} (threshold {:.2})\n\nActive session: {}\nSession cost:   {}\nToday's cost:   {}\nToday's tokens: {}\nBurn rate:      {}\nModel:          {}\n\nTime: {}\n",
+      alert.message,
+      alert.trigger_field,
+      alert.trigger_value,
+      alert.threshold,
+      if stats.active_session { "yes" } else { "no" },
+      self.currency.format(stats.session_cost),
+      self.currency.format(stats.cost),
+      stats.daily_tokens,
+      stats.burn_rate.map_or("n/a".to_string(), |rate| format!("{:.0} tokens/min", rate)),
+      stats.model,
+      alert.timestamp,
+    );
+    self.send(&subject, &body).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+  use tokio::net::TcpListener;
+  use tokio::task::JoinHandle;
+  use super::*;
+
+  /// A one-message SMTP sink that hands back the raw DATA it received.
+  async fn smtp_sink() -> (u16, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let sink = tokio::spawn(async move {
+      let (socket, _) = listener.accept().await.unwrap();
+      let (reader, mut writer) = socket.into_split();
+      let mut lines = BufReader::new(reader).lines();
+      writer.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+      let mut data = String::new();
+      let mut in_data = false;
+      while let Ok(Some(line)) = lines.next_line().await {
+        if in_data {
+          if line == "." {
+            in_data = false;
+            writer.write_all(b"250 queued\r\n").await.unwrap();
+          } else {
+            data.push_str(&line);
+            data.push('\n');
+          }
+          continue;
+        }
+        let command = line.to_ascii_uppercase();
+        let reply: &[u8] = if command.starts_with("DATA") {
+          in_data = true;
+          b"354 go ahead\r\n"
+        } else if command.starts_with("QUIT") {
+          writer.write_all(b"221 bye\r\n").await.unwrap();
+          break;
+        } else {
+          b"250 OK\r\n"
+        };
+        writer.write_all(reply).await.unwrap();
+      }
+      data
+    });
+    (port, sink)
+  }
+
+  #[tokio::test]
+  async fn delivers_to_a_plain_smtp_sink() {
+    let (port, sink) = smtp_sink().await;
+    let notifier = SmtpNotifier::new(SmtpConfig {
+      enabled: true,
+      host: "127.0.0.1".to_string(),
+      port,
+      security: SmtpSecurity::None,
+      from: "monitor@example.com".to_string(),
+      to: vec!["me@example.com".to_string()],
+      ..SmtpConfig::default()
+    }, CurrencyConfig::default())
+    .unwrap();
+
+    notifier.send("[Claude Token Monitor] Daily summary", "Claude usage daily summary\n").await.unwrap();
+    let data = sink.await.unwrap();
+    assert!(data.contains("Subject: [Claude Token Monitor] Daily summary"), "{}", data);
+    assert!(data.contains("To: me@example.com"), "{}", data);
+    assert!(data.contains("Claude usage daily summary"), "{}", data);
+  }
+}
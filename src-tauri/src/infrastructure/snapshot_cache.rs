@@ -0,0 +1,25 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::domain::entities::UsageSnapshot;
+use super::{atomic_file, settings_store::APP_IDENTIFIER};
+
+const SNAPSHOT_FILE: &str = "usage-snapshot.json";
+
+/// The app rewrites this after every poll so the CLI can answer without
+/// running ccusage, e.g. for Claude Code's status line.
+pub fn default_path() -> Result<PathBuf, String> {
+  let cache_dir = dirs::cache_dir().ok_or("Could not determine the platform cache directory")?;
+  Ok(cache_dir.join(APP_IDENTIFIER).join(SNAPSHOT_FILE))
+}
+
+pub fn read() -> Result<UsageSnapshot, String> {
+  let path = default_path()?;
+  let contents = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+  serde_json::from_str(&contents).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+pub fn write(snapshot: &UsageSnapshot) -> Result<(), String> {
+  let path = default_path()?;
+  let json = serde_json::to_string(snapshot).map_err(|e| format!("Failed to serialize usage snapshot: {}", e))?;
+  atomic_file::write(&path, &json)
+}
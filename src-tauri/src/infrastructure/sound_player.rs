@@ -0,0 +1,104 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{path::BaseDirectory, AppHandle, Manager};
+use crate::domain::entities::{AlertSeverity, SoundConfig};
+
+/// Resolves the sound for a severity: the user's file if set, otherwise the
+/// default bundled with the app resources.
+pub fn resolve_sound(app: &AppHandle, config: &SoundConfig, severity: AlertSeverity) -> Result<PathBuf, String> {
+  let path = match config.path_for(severity) {
+    Some(custom) => PathBuf::from(custom),
+    None => app.path()
+      .resolve(format!("sounds/{}.wav", severity_name(severity)), BaseDirectory::Resource)
+      .map_err(|e| format!("Failed to resolve bundled sound: {}", e))?,
+  };
+
+  if !path.is_file() {
+    return Err(format!("Sound file not found: {}", path.display()));
+  }
+  Ok(path)
+}
+
+pub async fn play_for_severity(app: &AppHandle, config: &SoundConfig, severity: AlertSeverity) -> Result<(), String> {
+  let path = resolve_sound(app, config, severity)?;
+  tauri::async_runtime::spawn_blocking(move || play_file(&path))
+    .await
+    .map_err(|e| format!("Sound playback task failed: {}", e))?
+}
+
+/// Plays a file through the native audio backend, falling back to the
+/// platform's command-line players when no output device can be opened.
+pub fn play_file(path: &Path) -> Result<(), String> {
+  let native_error = match play_native(path) {
+    Ok(()) => return Ok(()),
+    Err(e) => e,
+  };
+  println!("⚠️ Native audio playback failed ({}), trying command-line players", native_error);
+
+  let mut errors = vec![format!("native: {}", native_error)];
+  for mut command in fallback_players(path) {
+    let player = command.get_program().to_string_lossy().into_owned();
+    match command.status() {
+      Ok(status) if status.success() => return Ok(()),
+      Ok(status) => errors.push(format!("{}: exited with {}", player, status)),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => errors.push(format!("{}: not installed", player)),
+      Err(e) => errors.push(format!("{}: {}", player, e)),
+    }
+  }
+
+  Err(format!("Could not play {}: {}", path.display(), errors.join("; ")))
+}
+
+fn play_native(path: &Path) -> Result<(), String> {
+  let (_stream, handle) = rodio::OutputStream::try_default().map_err(|e| e.to_string())?;
+  let file = File::open(path).map_err(|e| e.to_string())?;
+  let source = rodio::Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+  let sink = rodio::Sink::try_new(&handle).map_err(|e| e.to_string())?;
+  sink.append(source);
+  sink.sleep_until_end();
+  Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn player(program: &str, args: &[&str], path: &Path) -> Command {
+  let mut command = Command::new(program);
+  command.args(args).arg(path);
+  command
+}
+
+// PipeWire, PulseAudio, then plain ALSA.
+#[cfg(target_os = "linux")]
+fn fallback_players(path: &Path) -> Vec<Command> {
+  vec![player("pw-play", &[], path), player("paplay", &[], path), player("aplay", &["-q"], path)]
+}
+
+#[cfg(target_os = "macos")]
+fn fallback_players(path: &Path) -> Vec<Command> {
+  vec![player("afplay", &[], path)]
+}
+
+/// PowerShell's `Media.SoundPlayer` plays WAV files on every Windows install.
+/// The path goes in through the environment so it needs no quoting.
+#[cfg(target_os = "windows")]
+fn fallback_players(path: &Path) -> Vec<Command> {
+  let mut command = Command::new("powershell");
+  command
+    .args(["-NoProfile", "-NonInteractive", "-Command", "(New-Object Media.SoundPlayer $env:CC_WIDGET_SOUND).PlaySync()"])
+    .env("CC_WIDGET_SOUND", path);
+  vec![command]
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn fallback_players(_path: &Path) -> Vec<Command> {
+  Vec::new()
+}
+
+fn severity_name(severity: AlertSeverity) -> &'static str {
+  match severity {
+    AlertSeverity::Info => "info",
+    AlertSeverity::Warning => "warning",
+    AlertSeverity::Critical => "critical",
+  }
+}
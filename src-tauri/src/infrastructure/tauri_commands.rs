@@ -1,33 +1,440 @@
-use std::sync::Arc;
-use tauri::{State, Manager};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
+use chrono::{DateTime, Duration, Local};
+use tokio::sync::Notify;
+use tauri::{AppHandle, Emitter, State};
 use crate::domain::{
-  entities::{UsageStats, UsagePeriodSummary},
+  entities::{
+    AlertKind, AlertRules, AlertSeverity, BudgetStatus, DailyUsage, DetectedBlockLimit, DigestSchedule, MonitorStatus, NotificationConfig, PeriodComparison, PlanConfig, PlanLimits,
+    ShortcutStatus, SmtpConfig, UsageBlock, UsageBreakdown, UsageHistory, UsageSnapshot, UsageStats, UsagePeriodSummary, WebhookConfig,
+  },
+  notifier::Notifier,
   repository::UsageRepository,
+  policy::PolicyStatus,
+  settings::{AppSettings, CurrencyConfig, SETTINGS_VERSION},
 };
-use crate::application::use_cases;
+use crate::application::{alerts::{self, PeriodCosts}, budgets, dashboard, digest, status_template, use_cases};
+use super::{
+  ccusage_repository::CcusageRepository, dashboard_window, event_stream::EventStream, local_api, mini_window, placement::{self, Origin}, policy_store::LoadedPolicy, settings_store::SettingsStore,
+  shortcut, smtp_notifier::SmtpNotifier, sound_player, tray, webhook_notifier::WebhookNotifier, window_manager,
+  window_state_store::{WindowState, WindowStateStore},
+};
+
+/// The daily report feeds the dashboard and the metrics written after every
+/// poll; refreshing it every few minutes is enough for both.
+const DAILY_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(300);
 
 pub struct AppState {
-  pub usage_repo: Arc<dyn UsageRepository + Send + Sync>,
+  pub usage_repo: RwLock<Arc<dyn UsageRepository + Send + Sync>>,
+  /// Bumped whenever the repository is rebuilt so the monitor can reset its state.
+  pub repo_generation: AtomicU64,
+  /// Settings as the user saved them; see `settings()` for the effective ones.
+  pub settings: Mutex<AppSettings>,
+  pub settings_store: SettingsStore,
+  pub policy: LoadedPolicy,
+  pub detected_limit: Mutex<Option<DetectedBlockLimit>>,
+  pub latest_usage: Mutex<Option<UsageStats>>,
+  /// When `latest_usage` was polled.
+  pub latest_usage_at: Mutex<Option<DateTime<Local>>>,
+  /// Period summaries and blocks for the local API, reused until the next poll is due.
+  pub summary_cache: Mutex<HashMap<String, (Instant, UsagePeriodSummary)>>,
+  pub blocks_cache: Mutex<Option<(Instant, Vec<UsageBlock>)>>,
+  pub daily_cache: Mutex<Option<(Instant, Vec<DailyUsage>)>>,
+  /// Held while the daily report is fetched, so the dashboard's parallel
+  /// requests share one ccusage run.
+  pub daily_fetch: tokio::sync::Mutex<()>,
+  /// Pushes polls, alerts and block changes to local API stream clients.
+  pub events: EventStream,
+  /// Wakes the background monitor early, e.g. after the settings changed.
+  pub monitor_wakeup: Notify,
+  pub monitoring_paused: AtomicBool,
+  pub alerts_snoozed_until: Mutex<Option<DateTime<Local>>>,
+  pub window_state: Mutex<WindowState>,
+  pub window_state_store: WindowStateStore,
+  pub shortcut_status: Mutex<ShortcutStatus>,
+}
+
+impl AppState {
+  pub fn repo(&self) -> Arc<dyn UsageRepository + Send + Sync> {
+    self.usage_repo.read().unwrap().clone()
+  }
+
+  pub fn user_settings(&self) -> Result<AppSettings, String> {
+    Ok(self.settings.lock().map_err(|e| e.to_string())?.clone())
+  }
+
+  /// User settings with the organization policy merged over them.
+  pub fn settings(&self) -> Result<AppSettings, String> {
+    let settings = self.user_settings()?;
+    Ok(match &self.policy.policy {
+      Some(policy) => policy.apply(&settings),
+      None => settings,
+    })
+  }
+
+  pub fn plan_limits(&self) -> Result<PlanLimits, String> {
+    let plan = self.settings()?.profile().plan.clone();
+    let detected = self.detected_limit.lock().map_err(|e| e.to_string())?.clone();
+    Ok(plan.limits(detected.as_ref()))
+  }
+
+  /// Validates and persists new settings, then applies them.
+  pub fn apply_settings(&self, app: &AppHandle, mut settings: AppSettings) -> Result<AppSettings, String> {
+    settings.version = SETTINGS_VERSION;
+    validate_settings(&settings)?;
+    self.settings_store.save(&settings)?;
+    self.install_settings(app, settings.clone())?;
+    Ok(settings)
+  }
+
+  /// Swaps in already validated settings: rebuilds the repository when the
+  /// active profile or its data roots changed, wakes the monitor and
+  /// notifies every window.
+  pub fn install_settings(&self, app: &AppHandle, settings: AppSettings) -> Result<(), String> {
+    let previous = std::mem::replace(&mut *self.settings.lock().map_err(|e| e.to_string())?, settings.clone());
+    if previous.active_profile != settings.active_profile || previous.profile().data_roots != settings.profile().data_roots {
+      println!("🔄 Profile '{}' is now active, re-initializing repository", settings.active_profile);
+      *self.usage_repo.write().map_err(|e| e.to_string())? =
+        Arc::new(CcusageRepository::with_data_roots(settings.profile().expanded_data_roots()));
+      *self.latest_usage.lock().map_err(|e| e.to_string())? = None;
+      *self.latest_usage_at.lock().map_err(|e| e.to_string())? = None;
+      self.summary_cache.lock().map_err(|e| e.to_string())?.clear();
+      *self.blocks_cache.lock().map_err(|e| e.to_string())? = None;
+      *self.daily_cache.lock().map_err(|e| e.to_string())? = None;
+      *self.detected_limit.lock().map_err(|e| e.to_string())? = None;
+      self.repo_generation.fetch_add(1, Ordering::SeqCst);
+    }
+    self.monitor_wakeup.notify_one();
+    shortcut::apply(app);
+    local_api::apply(app);
+    tray::refresh_menu(app);
+    tray::update_usage(app);
+    let _ = app.emit("settings-changed", &self.settings()?);
+    Ok(())
+  }
+
+  pub fn monitor_status(&self) -> MonitorStatus {
+    MonitorStatus {
+      paused: self.monitoring_paused.load(Ordering::SeqCst),
+      alerts_snoozed_until: self.snoozed_until().map(|until| until.to_rfc3339()),
+    }
+  }
+
+  /// The end of the current snooze, or `None` when alerts are live.
+  pub fn snoozed_until(&self) -> Option<DateTime<Local>> {
+    self.alerts_snoozed_until.lock().unwrap().filter(|until| *until > Local::now())
+  }
+
+  pub fn set_monitoring_paused(&self, app: &AppHandle, paused: bool) {
+    self.monitoring_paused.store(paused, Ordering::SeqCst);
+    println!("{} Monitoring {}", if paused { "⏸️" } else { "▶️" }, if paused { "paused" } else { "resumed" });
+    self.monitor_wakeup.notify_one();
+    self.monitor_status_changed(app);
+  }
+
+  /// Mutes alert sounds and notifications for `minutes`; 0 ends the snooze.
+  pub fn snooze_alerts(&self, app: &AppHandle, minutes: u32) {
+    let until = (minutes > 0).then(|| Local::now() + Duration::minutes(minutes as i64));
+    *self.alerts_snoozed_until.lock().unwrap() = until;
+    match until {
+      Some(until) => println!("🔕 Alerts snoozed until {}", until.format("%H:%M")),
+      None => println!("🔔 Alerts resumed"),
+    }
+    self.monitor_status_changed(app);
+  }
+
+  fn monitor_status_changed(&self, app: &AppHandle) {
+    tray::update_usage(app);
+    let _ = app.emit("monitor-status", self.monitor_status());
+  }
+
+  /// The latest polled stats, or `None` before the first poll.
+  pub fn usage_snapshot(&self) -> Option<UsageSnapshot> {
+    let stats = self.latest_usage.lock().unwrap().clone()?;
+    let updated_at = self.latest_usage_at.lock().unwrap().map(|at| at.to_rfc3339()).unwrap_or_default();
+    let profile = self.settings().map(|settings| settings.active_profile).unwrap_or_default();
+    Some(UsageSnapshot { profile, updated_at, stats })
+  }
+
+  fn cache_ttl(&self) -> std::time::Duration {
+    std::time::Duration::from_secs(self.settings().map(|settings| settings.poll_interval_secs).unwrap_or(60))
+  }
+
+  /// The summary for `period`, fetched at most once per poll interval.
+  pub async fn cached_summary(&self, period: &str) -> Result<UsagePeriodSummary, String> {
+    let ttl = self.cache_ttl();
+    let cached = self.summary_cache.lock().unwrap().get(period)
+      .filter(|(at, _)| at.elapsed() < ttl)
+      .map(|(_, summary)| summary.clone());
+    if let Some(summary) = cached {
+      return Ok(summary);
+    }
+    let summary = use_cases::get_usage_summary(self.repo(), period.to_string()).await?;
+    self.summary_cache.lock().unwrap().insert(period.to_string(), (Instant::now(), summary.clone()));
+    Ok(summary)
+  }
+
+  /// Usage blocks, fetched at most once per poll interval.
+  pub async fn cached_blocks(&self) -> Result<Vec<UsageBlock>, String> {
+    let ttl = self.cache_ttl();
+    let cached = self.blocks_cache.lock().unwrap().as_ref()
+      .filter(|(at, _)| at.elapsed() < ttl)
+      .map(|(_, blocks)| blocks.clone());
+    if let Some(blocks) = cached {
+      return Ok(blocks);
+    }
+    let blocks = use_cases::get_blocks(self.repo()).await?;
+    *self.blocks_cache.lock().unwrap() = Some((Instant::now(), blocks.clone()));
+    Ok(blocks)
+  }
+
+  /// The daily report with per-model splits, fetched at most once per poll
+  /// interval and never more often than `DAILY_CACHE_TTL`.
+  pub async fn cached_daily(&self) -> Result<Vec<DailyUsage>, String> {
+    let _fetching = self.daily_fetch.lock().await;
+    let ttl = self.cache_ttl().max(DAILY_CACHE_TTL);
+    let cached = self.daily_cache.lock().unwrap().as_ref()
+      .filter(|(at, _)| at.elapsed() < ttl)
+      .map(|(_, daily)| daily.clone());
+    if let Some(daily) = cached {
+      return Ok(daily);
+    }
+    let daily = self.repo().get_daily_usage().await?;
+    *self.daily_cache.lock().unwrap() = Some((Instant::now(), daily.clone()));
+    Ok(daily)
+  }
+
+  /// Week and month spend, fetched only for the budgets the rules define.
+  pub async fn period_costs(&self, rules: &AlertRules) -> PeriodCosts {
+    let mut period_costs = PeriodCosts::default();
+    if rules.weekly_budget_usd.is_some() {
+      period_costs.weekly_cost = self.cached_summary("week").await.ok().map(|summary| summary.total_cost);
+    }
+    if rules.monthly_budget_usd.is_some() {
+      period_costs.monthly_cost = self.cached_summary("month").await.ok().map(|summary| summary.total_cost);
+    }
+    period_costs
+  }
+
+  pub async fn budget_statuses(&self) -> Result<Vec<BudgetStatus>, String> {
+    let stats = self.latest_usage.lock().unwrap().clone().ok_or("Usage has not been polled yet")?;
+    let rules = self.settings()?.profile().notifications.rules.clone();
+    let period_costs = self.period_costs(&rules).await;
+    Ok(budgets::budget_statuses(&rules, &stats, &period_costs))
+  }
+
+  pub fn switch_profile(&self, app: &AppHandle, name: &str) -> Result<AppSettings, String> {
+    let mut settings = self.user_settings()?;
+    if !settings.profiles.contains_key(name) {
+      return Err(format!("Profile '{}' does not exist", name));
+    }
+    settings.active_profile = name.to_string();
+    self.apply_settings(app, settings)
+  }
+}
+
+/// Schema validation plus the checks that need the notification channels.
+pub fn validate_settings(settings: &AppSettings) -> Result<(), String> {
+  settings.validate()?;
+  status_template::validate_template(&settings.tray.tooltip_template).map_err(|e| format!("tray.tooltip_template: {}", e))?;
+  status_template::validate_template(&settings.tray.title_template).map_err(|e| format!("tray.title_template: {}", e))?;
+  if settings.shortcut.enabled && !settings.shortcut.accelerator.trim().is_empty() {
+    shortcut::parse_accelerator(settings.shortcut.accelerator.trim()).map_err(|e| format!("shortcut: {}", e))?;
+  }
+  for (name, profile) in &settings.profiles {
+    for webhook in profile.notifications.webhooks.iter().filter(|webhook| webhook.enabled) {
+      WebhookNotifier::new(webhook.clone()).map_err(|e| format!("profile '{}': {}", name, e))?;
+    }
+    if let Some(smtp) = profile.notifications.smtp.as_ref().filter(|smtp| smtp.enabled) {
+      SmtpNotifier::new(smtp.clone(), profile.currency.clone()).map_err(|e| format!("profile '{}': {}", name, e))?;
+    }
+  }
+  Ok(())
 }
 
 #[tauri::command]
 pub async fn get_claude_usage(state: State<'_, AppState>) -> Result<UsageStats, String> {
-  use_cases::get_claude_usage(state.usage_repo.clone()).await
+  let limits = state.plan_limits()?;
+  use_cases::get_claude_usage(state.repo(), &limits).await
 }
 
 #[tauri::command]
 pub async fn get_usage_summary(state: State<'_, AppState>, period: String) -> Result<UsagePeriodSummary, String> {
-  use_cases::get_usage_summary(state.usage_repo.clone(), period).await
+  use_cases::get_usage_summary(state.repo(), period).await
 }
 
 #[tauri::command]
-pub fn hide_main_window(app: tauri::AppHandle) -> Result<(), String> {
-  if let Some(window) = app.get_webview_window("main") {
-  window.hide().map_err(|e| e.to_string())?;
-  }
+pub async fn get_usage_history(state: State<'_, AppState>, period: String) -> Result<UsageHistory, String> {
+  dashboard::get_usage_history(&state.cached_daily().await?, &period)
+}
+
+#[tauri::command]
+pub async fn get_model_breakdown(state: State<'_, AppState>, period: String) -> Result<UsageBreakdown, String> {
+  dashboard::get_model_breakdown(&state.cached_daily().await?, &period)
+}
+
+#[tauri::command]
+pub async fn get_project_breakdown(state: State<'_, AppState>, period: String) -> Result<UsageBreakdown, String> {
+  dashboard::get_project_breakdown(state.repo(), period).await
+}
+
+#[tauri::command]
+pub async fn compare_periods(state: State<'_, AppState>, period: String) -> Result<PeriodComparison, String> {
+  dashboard::get_period_comparison(&state.cached_daily().await?, &period)
+}
+
+#[tauri::command]
+pub fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+  state.user_settings()
+}
+
+#[tauri::command]
+pub fn get_effective_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+  state.settings()
+}
+
+#[tauri::command]
+pub fn get_policy(state: State<'_, AppState>) -> Result<PolicyStatus, String> {
+  Ok(state.policy.status())
+}
+
+#[tauri::command]
+pub fn update_settings(app: AppHandle, state: State<'_, AppState>, settings: AppSettings) -> Result<AppSettings, String> {
+  state.apply_settings(&app, settings)
+}
+
+#[tauri::command]
+pub fn get_monitor_status(state: State<'_, AppState>) -> MonitorStatus {
+  state.monitor_status()
+}
+
+#[tauri::command]
+pub fn set_monitoring_paused(app: AppHandle, state: State<'_, AppState>, paused: bool) -> MonitorStatus {
+  state.set_monitoring_paused(&app, paused);
+  state.monitor_status()
+}
+
+#[tauri::command]
+pub fn snooze_alerts(app: AppHandle, state: State<'_, AppState>, minutes: u32) -> MonitorStatus {
+  state.snooze_alerts(&app, minutes);
+  state.monitor_status()
+}
+
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, state: State<'_, AppState>, name: String) -> Result<AppSettings, String> {
+  state.switch_profile(&app, &name)
+}
+
+#[tauri::command]
+pub fn get_plan(state: State<'_, AppState>) -> Result<PlanConfig, String> {
+  Ok(state.user_settings()?.profile().plan.clone())
+}
+
+#[tauri::command]
+pub fn update_plan(app: AppHandle, state: State<'_, AppState>, plan: PlanConfig) -> Result<PlanLimits, String> {
+  let mut settings = state.user_settings()?;
+  settings.profile_mut()?.plan = plan;
+  state.apply_settings(&app, settings)?;
+  state.plan_limits()
+}
+
+#[tauri::command]
+pub fn get_plan_limits(state: State<'_, AppState>) -> Result<PlanLimits, String> {
+  state.plan_limits()
+}
+
+#[tauri::command]
+pub async fn get_detected_block_limit(state: State<'_, AppState>) -> Result<Option<DetectedBlockLimit>, String> {
+  let detected = use_cases::detect_block_limit(state.repo()).await?;
+  *state.detected_limit.lock().map_err(|e| e.to_string())? = detected.clone();
+  Ok(detected)
+}
+
+#[tauri::command]
+pub fn get_notification_config(state: State<'_, AppState>) -> Result<NotificationConfig, String> {
+  Ok(state.user_settings()?.profile().notifications.clone())
+}
+
+#[tauri::command]
+pub fn update_notification_config(app: AppHandle, state: State<'_, AppState>, config: NotificationConfig) -> Result<(), String> {
+  let mut settings = state.user_settings()?;
+  settings.profile_mut()?.notifications = config;
+  state.apply_settings(&app, settings)?;
   Ok(())
 }
 
+#[tauri::command]
+pub async fn test_webhook(state: State<'_, AppState>, webhook: WebhookConfig) -> Result<(), String> {
+  let stats = state.latest_usage.lock().map_err(|e| e.to_string())?.clone();
+  let stats = match stats {
+    Some(stats) => stats,
+    None => use_cases::get_claude_usage(state.repo(), &state.plan_limits()?).await?,
+  };
+  let alert = alerts::build_alert(
+    AlertKind::BudgetWarning,
+    AlertSeverity::Critical,
+    "Claude Token Monitor test alert".to_string(),
+    "If you can read this, the webhook is configured correctly.".to_string(),
+    "session_cost",
+    stats.session_cost,
+    0.0,
+    &stats,
+  );
+  let notifier = WebhookNotifier::new(WebhookConfig { enabled: true, min_severity: AlertSeverity::Info, ..webhook })?;
+  notifier.notify(&alert).await
+}
+
+#[tauri::command]
+pub async fn test_smtp(smtp: SmtpConfig) -> Result<(), String> {
+  let notifier = SmtpNotifier::new(SmtpConfig { enabled: true, ..smtp }, CurrencyConfig::default())?;
+  notifier.send(
+    "[Claude Token Monitor] Test email",
+    "If you can read this, the SMTP channel is configured correctly.\n",
+  ).await
+}
+
+#[tauri::command]
+pub async fn send_usage_digest(state: State<'_, AppState>, schedule: DigestSchedule) -> Result<(), String> {
+  let settings = state.settings()?;
+  let profile = settings.profile();
+  let smtp = profile.notifications.smtp.clone().ok_or("SMTP channel is not configured")?;
+  let notifier = SmtpNotifier::new(smtp, profile.currency.clone())?;
+  let digest = digest::build_digest(state.repo(), schedule, &profile.currency).await?;
+  notifier.send(&digest.subject, &digest.body).await
+}
+
+#[tauri::command]
+pub fn show_main_window(app: tauri::AppHandle) -> Result<(), String> {
+  window_manager::show(&app, Origin::Default)
+}
+
+#[tauri::command]
+pub fn hide_main_window(app: tauri::AppHandle) -> Result<(), String> {
+  window_manager::hide(&app)
+}
+
+#[tauri::command]
+pub fn toggle_main_window(app: tauri::AppHandle) -> Result<bool, String> {
+  window_manager::toggle(&app, Origin::Default)
+}
+
+#[tauri::command]
+pub fn get_shortcut_status(state: State<'_, AppState>) -> ShortcutStatus {
+  state.shortcut_status.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn toggle_popup_pin(app: tauri::AppHandle) -> Result<bool, String> {
+  window_manager::toggle_pinned(&app)
+}
+
+#[tauri::command]
+pub fn is_popup_pinned() -> bool {
+  window_manager::is_pinned()
+}
+
 #[tauri::command]
 pub fn request_permissions() -> Result<(), String> {
   println!("🔍 Permission request called");
@@ -42,84 +449,22 @@ pub fn request_permissions() -> Result<(), String> {
   Ok(())
 }
 
+/// Async so the window is built off the main thread; a synchronous command
+/// that creates a window deadlocks on Windows.
 #[tauri::command]
-pub fn emergency_show_window(app: tauri::AppHandle) -> Result<String, String> {
-  println!("🚨 Emergency show window called!");
-
-  if let Some(window) = app.get_webview_window("main") {
-  println!("🔍 Window found, attempting emergency show");
-
-  // Force apply all settings
-  let _ = window.unminimize();
-  let _ = window.set_size(tauri::Size::Logical(tauri::LogicalSize { width: 400.0, height: 500.0 }));
-  let _ = window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x: 100.0, y: 100.0 }));
-  let _ = window.set_always_on_top(true);
-  let _ = window.set_focus();
-
-  match window.show() {
-    Ok(_) => {
-      println!("✅ Emergency show successful!");
-      Ok("Window displayed successfully!".to_string())
-    }
-    Err(e) => {
-      println!("❌ Emergency show failed: {}", e);
-      Err(format!("Failed to show window: {}", e))
-    }
-  }
-  } else {
-  println!("❌ Window not found!");
-  Err("Window not found".to_string())
-  }
+pub async fn toggle_mini_widget(app: tauri::AppHandle) -> Result<bool, String> {
+  mini_window::toggle(&app)
 }
 
+/// Async for the same reason as `toggle_mini_widget`.
 #[tauri::command]
-pub fn toggle_main_window(app: tauri::AppHandle) -> Result<(), String> {
-  println!("🔍 toggle_main_window command called");
-  if let Some(window) = app.get_webview_window("main") {
-  let is_visible = window.is_visible().unwrap_or(false);
-  println!("🔍 Window is visible: {}", is_visible);
-  match is_visible {
-    true => {
-      println!("🔍 Hiding window via command");
-      window.hide().map_err(|e| e.to_string())?;
-    }
-    false => {
-      println!("🔍 Showing window via command");
-      let _ = window.unminimize();
-      // Set position to center if not set
-      let _ = window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x: 100.0, y: 100.0 }));
-      window.show().map_err(|e| e.to_string())?;
-      let _ = window.set_focus();
-      println!("✅ Window should now be visible");
-    }
-  }
-  } else {
-  println!("❌ Main window not found in toggle command!");
-  return Err("Main window not found".to_string());
-  }
-  Ok(())
+pub async fn open_dashboard(app: tauri::AppHandle) -> Result<(), String> {
+  dashboard_window::open(&app)
 }
 
 #[tauri::command]
-pub fn force_show_window(app: tauri::AppHandle) -> Result<(), String> {
-  println!("🔍 Force show window called");
-  if let Some(window) = app.get_webview_window("main") {
-  println!("🔍 Found window, forcing it to show");
-
-  // Force position window to center and show
-  let _ = window.set_size(tauri::Size::Logical(tauri::LogicalSize { width: 350.0, height: 450.0 }));
-  let _ = window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x: 200.0, y: 200.0 }));
-  let _ = window.unminimize();
-  let _ = window.show();
-  let _ = window.set_focus();
-  let _ = window.set_always_on_top(true);
-
-  println!("✅ Force show completed");
-  Ok(())
-  } else {
-  println!("❌ Window not found");
-  Err("Window not found".to_string())
-  }
+pub fn reset_popup_position(app: tauri::AppHandle) -> Result<(), String> {
+  placement::reset_position(&app)
 }
 
 #[tauri::command]
@@ -180,31 +525,16 @@ pub async fn show_system_notification(
 }
 
 #[tauri::command]
-pub async fn play_warning_sound(urgent: bool) -> Result<(), String> {
-  use std::process::Command;
-
-  #[cfg(target_os = "macos")]
-  {
-      let sound = if urgent { "Basso" } else { "Ping" };
-      let mut cmd = Command::new("afplay");
-      cmd.arg(&format!("/System/Library/Sounds/{}.aiff", sound));
-      let _ = cmd.output();
-  }
-
-  #[cfg(target_os = "windows")]
-  {
-      let mut cmd = Command::new("powershell");
-      cmd.arg("-c");
-      cmd.arg(&format!("[console]::beep({})", if urgent { "800,500" } else { "400,200" }));
-      let _ = cmd.output();
-  }
+pub async fn play_warning_sound(app: tauri::AppHandle, state: State<'_, AppState>, urgent: bool) -> Result<(), String> {
+  let severity = if urgent { AlertSeverity::Critical } else { AlertSeverity::Warning };
+  play_alert_sound(app, state, severity).await
+}
 
-  #[cfg(target_os = "linux")]
-  {
-      let mut cmd = Command::new("paplay");
-      cmd.arg("/usr/share/sounds/alsa/Front_Right.wav");
-      let _ = cmd.output();
+#[tauri::command]
+pub async fn play_alert_sound(app: tauri::AppHandle, state: State<'_, AppState>, severity: AlertSeverity) -> Result<(), String> {
+  let sounds = state.settings()?.profile().notifications.sounds.clone();
+  if !sounds.enabled {
+    return Ok(());
   }
-
-  Ok(())
+  sound_player::play_for_severity(&app, &sounds, severity).await
 }
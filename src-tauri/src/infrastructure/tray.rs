@@ -1,182 +1,125 @@
 use tauri::{
   tray::{TrayIconBuilder, TrayIconEvent, MouseButton, MouseButtonState},
-  menu::{MenuBuilder, MenuItem},
-  Manager, AppHandle, Emitter, image::Image
+  Manager, AppHandle,
 };
+use std::sync::Mutex;
+use crate::application::status_template::{self, TemplateContext};
+use crate::domain::{
+  entities::UsageStats,
+  settings::{AppSettings, TrayGaugeMetric},
+};
+use super::{placement::Origin, tauri_commands::AppState, tray_icon::{self, Gauge}, tray_menu, window_manager};
 
-pub fn create_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-  let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-  let refresh = MenuItem::with_id(app, "refresh", "Refresh", true, None::<&str>)?;
+const TRAY_ID: &str = "main";
 
-  let menu = MenuBuilder::new(app)
-  .items(&[&refresh, &quit])
-  .build()?;
+/// The gauge currently shown, so unchanged updates don't swap the image.
+static CURRENT_GAUGE: Mutex<Option<Gauge>> = Mutex::new(None);
 
-  // Create purple circular tray icon
-  let icon = create_purple_tray_icon()?;
+/// Rebuilds the tray menu, e.g. after the profile list or active profile changed.
+pub fn refresh_menu(app: &AppHandle) {
+  let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+  match tray_menu::build(app) {
+    Ok(menu) => {
+      let _ = tray.set_menu(Some(menu));
+    }
+    Err(e) => println!("⚠️ Failed to rebuild tray menu: {}", e),
+  }
+}
 
-  let _tray = TrayIconBuilder::new()
+pub fn create_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+  let menu = tray_menu::build(app)?;
+
+  let settings = app.state::<AppState>().settings().unwrap_or_default();
+  let gauge = current_gauge(app, &settings, None);
+  let icon = tray_icon::render(&gauge);
+
+  let _tray = TrayIconBuilder::with_id(TRAY_ID)
   .menu(&menu)
   .show_menu_on_left_click(false)
   .icon(icon)
-  .on_menu_event(move |app, event| {
-    match event.id.as_ref() {
-      "quit" => app.exit(0),
-      "refresh" => {
-    if let Some(window) = app.get_webview_window("main") {
-      let _ = window.emit("refresh-usage", ());
-    }
-      }
-      _ => {}
-    }
-  })
+  .icon_as_template(gauge.monochrome)
+  .tooltip("Claude Token Monitor")
+  .on_menu_event(|app, event| tray_menu::handle_event(app, event.id.as_ref()))
   .on_tray_icon_event(|tray, event| {
     match event {
-      TrayIconEvent::Click { button, rect, button_state, .. } => {
-    let app = tray.app_handle();
-    if matches!(button, MouseButton::Left) && matches!(button_state, MouseButtonState::Down) {
-      let app_clone = app.clone();
-      let rect_clone = rect.clone();
-      std::thread::spawn(move || {
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        if let Some(window) = app_clone.get_webview_window("main") {
-          let is_visible = window.is_visible().unwrap_or(false);
-          if is_visible {
-      let _ = window.hide();
-          } else {
-      let _ = position_popup_near_tray(&window, rect_clone);
-      let _ = window.set_size(tauri::Size::Logical(tauri::LogicalSize { width: 400.0, height: 580.0 }));
-      let _ = window.unminimize();
-      let _ = window.show();
-      let _ = window.set_always_on_top(true);
-      let _ = window.set_focus();
-      println!("🔍 Tray click: Window shown and focused");
-          }
+      TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Down, rect, .. } => {
+        if let Err(e) = window_manager::toggle(tray.app_handle(), Origin::Tray(rect)) {
+          println!("❌ Failed to toggle popup from tray: {}", e);
         }
-      });
-    }
       }
       _ => {}
     }
   })
   .build(app)?;
 
+  *CURRENT_GAUGE.lock().unwrap() = Some(gauge);
   Ok(())
 }
 
-fn position_popup_near_tray(
-  window: &tauri::WebviewWindow,
-  tray_rect: tauri::Rect
-) -> Result<(), Box<dyn std::error::Error>> {
-  let is_empty_rect = match (tray_rect.position, tray_rect.size) {
-  (tauri::Position::Physical(pos), tauri::Size::Physical(size)) => {
-    pos.x == 0 && pos.y == 0 && size.width == 0 && size.height == 0
-  }
-  (tauri::Position::Logical(pos), tauri::Size::Logical(size)) => {
-    pos.x == 0.0 && pos.y == 0.0 && size.width == 0.0 && size.height == 0.0
-  }
-  _ => false,
-  };
-
-  if is_empty_rect {
-  let actual_tray_x = 896.0;
-  let x = actual_tray_x;
-  let y = 37.0;
-  window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }))?;
-  return Ok(());
-  }
-  let monitor = window.current_monitor()?.ok_or("No monitor found")?;
-  let monitor_size = monitor.size();
-  let scale = monitor.scale_factor();
-
-  let window_width = 400.0;
-  let window_height = 580.0;
-  let padding = 2.0;
-
-  let (tray_x, tray_y) = match tray_rect.position {
-  tauri::Position::Physical(pos) => (pos.x as f64 / scale, pos.y as f64 / scale),
-  tauri::Position::Logical(pos) => (pos.x, pos.y),
+/// Re-renders the tray gauge, tooltip, title and menu rows from the latest usage.
+pub fn update_usage(app: &AppHandle) {
+  let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+  let state = app.state::<AppState>();
+  let settings = state.settings().unwrap_or_default();
+  let stats = state.latest_usage.lock().unwrap().clone();
+
+  let render = |template: &str| match &stats {
+    Some(stats) if !template.is_empty() => Some(status_template::render_template(template, &TemplateContext {
+      stats,
+      currency: &settings.profile().currency,
+      profile: &settings.active_profile,
+    })),
+    _ => None,
   };
+  let tooltip = render(&settings.tray.tooltip_template).unwrap_or_else(|| "Claude Token Monitor".to_string());
+  let _ = tray.set_tooltip(Some(tooltip));
+  let _ = tray.set_title(render(&settings.tray.title_template));
 
-  let screen_width = monitor_size.width as f64 / scale;
-  let screen_height = monitor_size.height as f64 / scale;
+  update_icon(&tray, current_gauge(app, &settings, stats.as_ref()));
+  tray_menu::update(app);
+}
 
-  let mut popup_x = tray_x;
-  if popup_x + window_width > screen_width {
-  popup_x = screen_width - window_width - padding;
+/// Redraws the tray gauge unless it would look the same as the current one.
+/// The lock is released before drawing: tray calls off the main thread wait
+/// for it, and main-thread menu handlers end up here too.
+fn update_icon(tray: &tauri::tray::TrayIcon, gauge: Gauge) {
+  {
+    let mut current = CURRENT_GAUGE.lock().unwrap();
+    if *current == Some(gauge) {
+      return;
+    }
+    *current = Some(gauge);
   }
-  if popup_x < 0.0 {
-  popup_x = padding;
+  // Swapping the image in place on the existing handle avoids the flicker
+  // of removing and re-adding the tray icon.
+  if let Err(e) = tray.set_icon(Some(tray_icon::render(&gauge))) {
+    println!("⚠️ Failed to update tray icon: {}", e);
+    // Forget the gauge so the next update tries again.
+    let mut current = CURRENT_GAUGE.lock().unwrap();
+    if *current == Some(gauge) {
+      *current = None;
+    }
+    return;
   }
+  let _ = tray.set_icon_as_template(gauge.monochrome);
+}
 
-  let (_, size_height) = match tray_rect.size {
-  tauri::Size::Physical(size) => (size.width as f64 / scale, size.height as f64 / scale),
-  tauri::Size::Logical(size) => (size.width, size.height),
-  };
+fn current_gauge(app: &AppHandle, settings: &AppSettings, stats: Option<&UsageStats>) -> Gauge {
+  let percent = stats.and_then(|stats| gauge_percent(settings, stats));
+  let scale = app.primary_monitor().ok().flatten()
+    .map_or(1, |monitor| if monitor.scale_factor() > 1.0 { 2 } else { 1 });
+  Gauge::new(percent, &settings.thresholds, settings.tray.icon_style, settings.tray.monochrome, scale)
+}
 
-  let mut popup_y = tray_y + size_height + padding;
-  if popup_y + window_height > screen_height {
-  popup_y = tray_y - window_height - padding;
+fn gauge_percent(settings: &AppSettings, stats: &UsageStats) -> Option<f32> {
+  if !stats.active_session {
+    return Some(0.0);
   }
-  if popup_y < 0.0 {
-  popup_y = padding;
+  match settings.tray.gauge_metric {
+    TrayGaugeMetric::Block => stats.percent_of_block_limit,
+    TrayGaugeMetric::Budget => settings.profile().notifications.rules.block_budget_usd
+      .filter(|budget| *budget > 0.0)
+      .map(|budget| stats.session_cost / budget * 100.0),
   }
-  window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x: popup_x, y: popup_y }))?;
-  Ok(())
 }
 
-fn create_purple_tray_icon() -> Result<Image<'static>, Box<dyn std::error::Error>> {
-  // Create a 16x16 colorful monitoring icon with chart elements
-  let size = 16;
-  let mut rgba_data = Vec::with_capacity(size * size * 4);
-
-  for y in 0..size {
-    for x in 0..size {
-      // Create a blue circle background
-      let center_x = size as f32 / 2.0;
-      let center_y = size as f32 / 2.0;
-      let distance = ((x as f32 - center_x).powi(2) + (y as f32 - center_y).powi(2)).sqrt();
-
-      if distance <= 6.0 {
-        // Blue background (34, 139, 230)
-        let mut r = 34;
-        let mut g = 139;
-        let mut b = 230;
-        let a = 255;
-        
-        // Add colorful chart elements
-        let is_chart_line = (y == 6 && x >= 4 && x <= 12) ||
-                           (y == 8 && x >= 4 && x <= 11) ||
-                           (y == 10 && x >= 4 && x <= 10) ||
-                           (y == 12 && x >= 4 && x <= 12);
-        
-        // Colorful chart points
-        if x == 5 && y == 6 {
-          r = 52; g = 199; b = 89; // Green
-        } else if x == 7 && y == 5 {
-          r = 255; g = 204; b = 0; // Yellow
-        } else if x == 9 && y == 4 {
-          r = 255; g = 94; b = 132; // Pink
-        } else if x == 11 && y == 3 {
-          r = 90; g = 200; b = 250; // Light Blue
-        } else if is_chart_line {
-          r = 255; g = 255; b = 255; // White lines
-        }
-        
-        rgba_data.push(r);
-        rgba_data.push(g);
-        rgba_data.push(b);
-        rgba_data.push(a);
-      } else {
-        // Transparent
-        rgba_data.push(0);
-        rgba_data.push(0);
-        rgba_data.push(0);
-        rgba_data.push(0);
-      }
-    }
-  }
-
-  let image = Image::new_owned(rgba_data, size as u32, size as u32);
-  Ok(image)
-}
@@ -0,0 +1,143 @@
+use tauri::image::Image;
+use crate::domain::settings::{TrayIconStyle, WarningThresholds};
+
+/// Logical tray icon size; @2x renders at twice this many pixels.
+const BASE_SIZE: u32 = 16;
+/// Samples per axis used to anti-alias each pixel.
+const SUPERSAMPLE: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GaugeLevel {
+  Unknown,
+  Normal,
+  Warning,
+  Critical,
+  Danger,
+}
+
+impl GaugeLevel {
+  pub fn from_percent(percent: Option<f32>, thresholds: &WarningThresholds) -> Self {
+    match percent {
+      None => GaugeLevel::Unknown,
+      Some(percent) if percent >= thresholds.danger => GaugeLevel::Danger,
+      Some(percent) if percent >= thresholds.critical => GaugeLevel::Critical,
+      Some(percent) if percent >= thresholds.warning => GaugeLevel::Warning,
+      Some(_) => GaugeLevel::Normal,
+    }
+  }
+
+  fn color(self) -> [u8; 3] {
+    match self {
+      GaugeLevel::Unknown => [34, 139, 230],
+      GaugeLevel::Normal => [52, 199, 89],
+      GaugeLevel::Warning => [255, 204, 0],
+      GaugeLevel::Critical => [255, 149, 0],
+      GaugeLevel::Danger => [255, 59, 48],
+    }
+  }
+}
+
+/// Everything that affects the rendered pixels. Two equal gauges produce the
+/// same image, so callers can skip redundant swaps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gauge {
+  /// Whole percent, clamped to 0..=100.
+  pub percent: Option<u8>,
+  pub level: GaugeLevel,
+  pub style: TrayIconStyle,
+  /// Black-only template image that the menubar recolours for dark and light themes.
+  pub monochrome: bool,
+  /// 1 for @1x, 2 for @2x.
+  pub scale: u32,
+}
+
+impl Gauge {
+  pub fn new(percent: Option<f32>, thresholds: &WarningThresholds, style: TrayIconStyle, monochrome: bool, scale: u32) -> Self {
+    Self {
+      percent: percent.map(|percent| percent.clamp(0.0, 100.0).round() as u8),
+      level: GaugeLevel::from_percent(percent, thresholds),
+      style,
+      monochrome,
+      scale: scale.clamp(1, 2),
+    }
+  }
+}
+
+/// Renders the gauge as an RGBA tray icon.
+pub fn render(gauge: &Gauge) -> Image<'static> {
+  let size = BASE_SIZE * gauge.scale;
+  let fill = gauge.percent.unwrap_or(0) as f32 / 100.0;
+  let (color, track_alpha) = if gauge.monochrome {
+    ([0, 0, 0], 0.35)
+  } else {
+    (gauge.level.color(), 0.3)
+  };
+  let track_color = if gauge.monochrome { [0, 0, 0] } else { [142, 142, 147] };
+
+  let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+  for y in 0..size {
+    for x in 0..size {
+      let mut fill_coverage = 0.0;
+      let mut track_coverage = 0.0;
+      for sy in 0..SUPERSAMPLE {
+        for sx in 0..SUPERSAMPLE {
+          // Sample position in logical 16x16 units.
+          let px = (x as f32 + (sx as f32 + 0.5) / SUPERSAMPLE as f32) / gauge.scale as f32;
+          let py = (y as f32 + (sy as f32 + 0.5) / SUPERSAMPLE as f32) / gauge.scale as f32;
+          match sample(gauge, px, py, fill) {
+            Sample::Fill => fill_coverage += 1.0,
+            Sample::Track => track_coverage += 1.0,
+            Sample::Empty => {}
+          }
+        }
+      }
+      let samples = (SUPERSAMPLE * SUPERSAMPLE) as f32;
+      let fill_alpha = fill_coverage / samples;
+      let track_alpha = track_coverage / samples * track_alpha;
+      let alpha = fill_alpha + track_alpha;
+      if alpha <= 0.0 {
+        rgba.extend_from_slice(&[0, 0, 0, 0]);
+        continue;
+      }
+      // Straight (non-premultiplied) alpha: blend the two colours by their share of coverage.
+      let mix = |channel: usize| ((color[channel] as f32 * fill_alpha + track_color[channel] as f32 * track_alpha) / alpha) as u8;
+      rgba.extend_from_slice(&[mix(0), mix(1), mix(2), (alpha.min(1.0) * 255.0) as u8]);
+    }
+  }
+  Image::new_owned(rgba, size, size)
+}
+
+enum Sample {
+  Fill,
+  Track,
+  Empty,
+}
+
+fn sample(gauge: &Gauge, x: f32, y: f32, fill: f32) -> Sample {
+  let center = BASE_SIZE as f32 / 2.0;
+  let (dx, dy) = (x - center, y - center);
+  let distance = (dx * dx + dy * dy).sqrt();
+
+  // A centre dot keeps the danger level visible in the monochrome variant too.
+  if gauge.level == GaugeLevel::Danger && distance <= 2.0 {
+    return Sample::Fill;
+  }
+
+  match gauge.style {
+    TrayIconStyle::Ring => {
+      if !(4.5..=7.5).contains(&distance) {
+        return Sample::Empty;
+      }
+      // Clockwise from 12 o'clock, as a fraction of a full turn.
+      let angle = (dx.atan2(-dy) / std::f32::consts::TAU).rem_euclid(1.0);
+      if angle < fill { Sample::Fill } else { Sample::Track }
+    }
+    TrayIconStyle::Bar => {
+      let (left, right, top, bottom) = (1.0, BASE_SIZE as f32 - 1.0, 5.0, 11.0);
+      if x < left || x > right || y < top || y > bottom {
+        return Sample::Empty;
+      }
+      if x < left + (right - left) * fill { Sample::Fill } else { Sample::Track }
+    }
+  }
+}
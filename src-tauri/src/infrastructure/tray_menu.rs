@@ -0,0 +1,260 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+use tauri::{
+  menu::{CheckMenuItem, Menu, MenuBuilder, MenuItem, SubmenuBuilder},
+  AppHandle, Emitter, Manager, Wry,
+};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_opener::OpenerExt;
+use crate::application::{status_template::{self, TemplateContext}, use_cases};
+use super::{dashboard_window, mini_window, tauri_commands::AppState};
+
+const PROFILE_ITEM_PREFIX: &str = "profile:";
+const PERIOD_ITEM_PREFIX: &str = "period:";
+const PERIODS: [(&str, &str); 3] = [("day", "Today"), ("week", "This Week"), ("month", "This Month")];
+const SNOOZE_MINUTES: u32 = 60;
+
+/// Items whose text follows the latest usage. They are updated in place
+/// because rebuilding the menu would close it while the user has it open.
+#[derive(Clone)]
+struct LiveItems {
+  today: MenuItem<Wry>,
+  block: MenuItem<Wry>,
+  pause: MenuItem<Wry>,
+  snooze: MenuItem<Wry>,
+  mini: CheckMenuItem<Wry>,
+}
+
+static LIVE_ITEMS: Mutex<Option<LiveItems>> = Mutex::new(None);
+
+pub fn build(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+  let settings = app.state::<AppState>().user_settings().unwrap_or_default();
+
+  let live = LiveItems {
+    today: MenuItem::with_id(app, "info_today", "Today: -", false, None::<&str>)?,
+    block: MenuItem::with_id(app, "info_block", "Block: -", false, None::<&str>)?,
+    pause: MenuItem::with_id(app, "toggle_pause", "Pause Monitoring", true, None::<&str>)?,
+    snooze: MenuItem::with_id(app, "toggle_snooze", "Snooze Alerts for 1 Hour", true, None::<&str>)?,
+    mini: CheckMenuItem::with_id(app, "toggle_mini", "Mini Widget", true, false, None::<&str>)?,
+  };
+  let refresh = MenuItem::with_id(app, "refresh", "Refresh", true, None::<&str>)?;
+  let dashboard = MenuItem::with_id(app, "open_dashboard", "Open Dashboard", true, None::<&str>)?;
+  let copy_summary = MenuItem::with_id(app, "copy_summary", "Copy Summary", true, None::<&str>)?;
+  let open_logs = MenuItem::with_id(app, "open_logs", "Open Claude Logs Folder", true, None::<&str>)?;
+  let open_settings = MenuItem::with_id(app, "open_settings", "Open Settings File", true, None::<&str>)?;
+  let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+
+  let mut periods = SubmenuBuilder::new(app, "Summary Period");
+  for (period, label) in PERIODS {
+    let id = format!("{}{}", PERIOD_ITEM_PREFIX, period);
+    let item = CheckMenuItem::with_id(app, id, label, true, settings.default_period == period, None::<&str>)?;
+    periods = periods.item(&item);
+  }
+  let periods = periods.build()?;
+
+  let mut profiles = SubmenuBuilder::new(app, "Profiles");
+  for name in settings.profiles.keys() {
+    let id = format!("{}{}", PROFILE_ITEM_PREFIX, name);
+    let item = CheckMenuItem::with_id(app, id, name, true, *name == settings.active_profile, None::<&str>)?;
+    profiles = profiles.item(&item);
+  }
+  let profiles = profiles.build()?;
+
+  let menu = MenuBuilder::new(app)
+  .items(&[&live.today, &live.block])
+  .separator()
+  .items(&[&refresh, &live.pause, &live.snooze])
+  .separator()
+  .items(&[&dashboard, &live.mini])
+  .separator()
+  .items(&[&periods, &copy_summary])
+  .separator()
+  .items(&[&profiles, &open_logs, &open_settings])
+  .separator()
+  .item(&quit)
+  .build()?;
+
+  update_live_items(app, &live);
+  *LIVE_ITEMS.lock().unwrap() = Some(live);
+  Ok(menu)
+}
+
+/// Refreshes the info rows and toggle labels from the current state. The
+/// handles are cloned out first: setting text off the main thread waits for
+/// it, and menu handlers on the main thread take this lock too.
+pub fn update(app: &AppHandle) {
+  let live = LIVE_ITEMS.lock().unwrap().clone();
+  if let Some(live) = live {
+    update_live_items(app, &live);
+  }
+}
+
+fn update_live_items(app: &AppHandle, live: &LiveItems) {
+  let state = app.state::<AppState>();
+  let settings = state.settings().unwrap_or_default();
+  let stats = state.latest_usage.lock().unwrap().clone();
+
+  let (today, block) = match &stats {
+    Some(stats) => {
+      let context = TemplateContext { stats, currency: &settings.profile().currency, profile: &settings.active_profile };
+      let today = status_template::render_template("Today: {daily_cost:$} · {daily_tokens:k} tokens", &context);
+      let block = if stats.active_session {
+        status_template::render_template("Block: {session_cost:$} · {time_left} left", &context)
+      } else {
+        "Block: no active block".to_string()
+      };
+      (today, block)
+    }
+    None => ("Today: -".to_string(), "Block: -".to_string()),
+  };
+  let _ = live.today.set_text(today);
+  let _ = live.block.set_text(block);
+
+  let paused = state.monitoring_paused.load(Ordering::SeqCst);
+  let _ = live.pause.set_text(if paused { "Resume Monitoring" } else { "Pause Monitoring" });
+  let _ = live.snooze.set_text(match state.snoozed_until() {
+    Some(until) => format!("Resume Alerts (snoozed until {})", until.format("%H:%M")),
+    None => "Snooze Alerts for 1 Hour".to_string(),
+  });
+  let _ = live.mini.set_checked(state.window_state.lock().unwrap().mini_open);
+}
+
+pub fn handle_event(app: &AppHandle, id: &str) {
+  let state = app.state::<AppState>();
+  match id {
+    "quit" => app.exit(0),
+    "refresh" => {
+      state.monitor_wakeup.notify_one();
+      if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("refresh-usage", ());
+      }
+    }
+    "toggle_pause" => {
+      let paused = state.monitoring_paused.load(Ordering::SeqCst);
+      state.set_monitoring_paused(app, !paused);
+    }
+    "toggle_snooze" => {
+      let minutes = if state.snoozed_until().is_some() { 0 } else { SNOOZE_MINUTES };
+      state.snooze_alerts(app, minutes);
+    }
+    // Building a window inside a menu handler deadlocks on Windows, so the
+    // dashboard and mini widget are created on the async runtime instead.
+    "open_dashboard" => {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        if let Err(e) = dashboard_window::open(&app) {
+          println!("❌ Failed to open dashboard: {}", e);
+        }
+      });
+    }
+    "toggle_mini" => {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        if let Err(e) = mini_window::toggle(&app) {
+          println!("❌ Failed to toggle mini widget: {}", e);
+          update(&app);
+        }
+      });
+    }
+    "copy_summary" => {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        if let Err(e) = copy_summary(&app).await {
+          println!("❌ Failed to copy summary: {}", e);
+        }
+      });
+    }
+    "open_logs" => {
+      if let Err(e) = open_logs_folder(app) {
+        println!("❌ Failed to open logs folder: {}", e);
+      }
+    }
+    "open_settings" => {
+      if let Err(e) = open_settings_file(app) {
+        println!("❌ Failed to open settings file: {}", e);
+      }
+    }
+    id => {
+      if let Some(name) = id.strip_prefix(PROFILE_ITEM_PREFIX) {
+        if let Err(e) = state.switch_profile(app, name) {
+          println!("❌ Failed to switch profile: {}", e);
+          super::tray::refresh_menu(app);
+        }
+      } else if let Some(period) = id.strip_prefix(PERIOD_ITEM_PREFIX) {
+        if let Err(e) = switch_period(app, period) {
+          println!("❌ Failed to switch summary period: {}", e);
+          super::tray::refresh_menu(app);
+        }
+      }
+    }
+  }
+}
+
+fn switch_period(app: &AppHandle, period: &str) -> Result<(), String> {
+  let state = app.state::<AppState>();
+  let mut settings = state.user_settings()?;
+  settings.default_period = period.to_string();
+  state.apply_settings(app, settings)?;
+  let _ = app.emit("summary-period-changed", period);
+  Ok(())
+}
+
+async fn copy_summary(app: &AppHandle) -> Result<(), String> {
+  let state = app.state::<AppState>();
+  let settings = state.settings()?;
+  let currency = &settings.profile().currency;
+  let summary = use_cases::get_usage_summary(state.repo(), settings.default_period.clone()).await?;
+
+  let mut text = format!(
+    "Claude usage {} to {} ({})\nTokens: {} ({:.0} per day)\nCost: {} ({} per day)\n",
+    summary.start_date,
+    summary.end_date,
+    settings.active_profile,
+    summary.total_tokens,
+    summary.avg_tokens_per_day,
+    currency.format(summary.total_cost),
+    currency.format(summary.avg_cost_per_day),
+  );
+  let stats = state.latest_usage.lock().map_err(|e| e.to_string())?.clone();
+  if let Some(stats) = stats.filter(|stats| stats.active_session) {
+    let context = TemplateContext { stats: &stats, currency, profile: &settings.active_profile };
+    text.push_str(&status_template::render_template(
+      "Active block: {session_cost:$} · {session_tokens} tokens · {block_percent} of limit · {time_left} left\n",
+      &context,
+    ));
+  }
+
+  app.clipboard().write_text(text).map_err(|e| e.to_string())?;
+  println!("📋 Usage summary copied to clipboard");
+  Ok(())
+}
+
+/// The `projects` folder holding Claude's JSONL usage logs for the active profile.
+fn open_logs_folder(app: &AppHandle) -> Result<(), String> {
+  let settings = app.state::<AppState>().settings()?;
+  let mut roots = settings.profile().expanded_data_roots();
+  if roots.is_empty() {
+    let home = dirs::home_dir().ok_or("Could not determine the home directory")?;
+    roots = vec![home.join(".config").join("claude"), home.join(".claude")];
+  }
+  let folder = roots.iter()
+    .map(|root| root.join("projects"))
+    .chain(roots.iter().cloned())
+    .find(|path| path.is_dir())
+    .ok_or("No Claude data directory found")?;
+  open_path(app, folder)
+}
+
+fn open_settings_file(app: &AppHandle) -> Result<(), String> {
+  let state = app.state::<AppState>();
+  let path = state.settings_store.path().to_path_buf();
+  if !path.exists() {
+    state.settings_store.save(&state.user_settings()?)?;
+  }
+  open_path(app, path)
+}
+
+fn open_path(app: &AppHandle, path: PathBuf) -> Result<(), String> {
+  app.opener().open_path(path.display().to_string(), None::<&str>).map_err(|e| e.to_string())
+}
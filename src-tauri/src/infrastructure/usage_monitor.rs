@@ -0,0 +1,202 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use chrono::{Local, Utc};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::application::{
+  alerts::AlertEngine,
+  block_tracker::BlockTracker,
+  digest,
+  use_cases,
+};
+use crate::domain::{
+  entities::{Alert, NotificationConfig},
+  notifier::Notifier,
+  settings::CurrencyConfig,
+};
+use super::{api_schema::StreamEvent, digest_log, metrics_exporter, smtp_notifier::SmtpNotifier, snapshot_cache, sound_player, tauri_commands::AppState, tray, webhook_notifier::WebhookNotifier};
+
+/// Block history changes slowly; re-detect the block limit every 30 polls.
+const LIMIT_DETECTION_EVERY: u32 = 30;
+
+/// Polls usage in the background, caches the latest stats in `AppState`
+/// and dispatches alerts to the configured notification channels.
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let mut engine = AlertEngine::new();
+    let mut blocks = BlockTracker::new();
+    let mut polls: u32 = 0;
+    let mut generation = app.state::<AppState>().repo_generation.load(Ordering::SeqCst);
+    loop {
+      // A profile switch starts the scheduler over against the new repository.
+      let current_generation = app.state::<AppState>().repo_generation.load(Ordering::SeqCst);
+      if current_generation != generation {
+        generation = current_generation;
+        engine = AlertEngine::new();
+        blocks = BlockTracker::new();
+        polls = 0;
+      }
+      let state = app.state::<AppState>();
+      if !state.monitoring_paused.load(Ordering::SeqCst) {
+        if polls % LIMIT_DETECTION_EVERY == 0 {
+          refresh_detected_limit(&app).await;
+        }
+        polls = polls.wrapping_add(1);
+        poll_once(&app, &mut engine, &mut blocks).await;
+        send_digest_if_due(&app).await;
+      }
+      let poll_interval = state.settings()
+        .map(|settings| settings.poll_interval_secs)
+        .unwrap_or(60);
+      tokio::select! {
+        _ = tokio::time::sleep(Duration::from_secs(poll_interval)) => {}
+        _ = state.monitor_wakeup.notified() => {}
+      }
+    }
+  });
+}
+
+async fn poll_once(app: &AppHandle, engine: &mut AlertEngine, blocks: &mut BlockTracker) {
+  let state = app.state::<AppState>();
+  let limits = match state.plan_limits() {
+    Ok(limits) => limits,
+    Err(e) => {
+      println!("⚠️ Failed to read plan limits: {}", e);
+      return;
+    }
+  };
+  let stats = match use_cases::get_claude_usage(state.repo(), &limits).await {
+    Ok(stats) => stats,
+    Err(e) => {
+      println!("⚠️ Background usage poll failed: {}", e.lines().next().unwrap_or_default());
+      return;
+    }
+  };
+
+  *state.latest_usage.lock().unwrap() = Some(stats.clone());
+  *state.latest_usage_at.lock().unwrap() = Some(Local::now());
+  let _ = app.emit("usage-updated", &stats);
+  if let Some(snapshot) = state.usage_snapshot() {
+    if let Err(e) = snapshot_cache::write(&snapshot) {
+      println!("⚠️ Failed to write usage snapshot: {}", e);
+    }
+    state.events.publish(StreamEvent::Usage(snapshot));
+  }
+  tray::update_usage(app);
+
+  let (config, currency) = match state.settings() {
+    Ok(settings) => (settings.profile().notifications.clone(), settings.profile().currency.clone()),
+    Err(e) => {
+      println!("⚠️ Failed to read settings: {}", e);
+      return;
+    }
+  };
+  let period_costs = state.period_costs(&config.rules).await;
+
+  // While snoozed the engine leaves new conditions armed so they still fire
+  // afterwards; block events are only timely now and are dropped.
+  let snoozed = state.snoozed_until().is_some();
+  let mut alerts = if snoozed {
+    engine.evaluate_muted(&config.rules, &stats, &period_costs);
+    Vec::new()
+  } else {
+    engine.evaluate(&config.rules, &stats, &period_costs)
+  };
+  let block_alerts = blocks.update(&config.rules, &stats, &limits, Utc::now());
+  if !snoozed {
+    alerts.extend(block_alerts);
+  }
+  for alert in alerts {
+    fire_alert(app, &config, &currency, &alert).await;
+  }
+  if state.events.has_subscribers() {
+    if let Ok(blocks) = state.cached_blocks().await {
+      state.events.publish_block(blocks.into_iter().find(|block| block.is_active));
+    }
+  }
+  metrics_exporter::write_textfile(&state).await;
+}
+
+async fn refresh_detected_limit(app: &AppHandle) {
+  let state = app.state::<AppState>();
+  match use_cases::detect_block_limit(state.repo()).await {
+    Ok(detected) => {
+      if let Some(limit) = &detected {
+        println!("🔍 Detected block limit: {} tokens ({:?} confidence, {} limit hits)", limit.block_tokens, limit.confidence, limit.limit_hits);
+      }
+      *state.detected_limit.lock().unwrap() = detected;
+    }
+    Err(e) => println!("⚠️ Block limit detection failed: {}", e.lines().next().unwrap_or_default()),
+  }
+}
+
+async fn send_digest_if_due(app: &AppHandle) {
+  let state = app.state::<AppState>();
+  let Ok(settings) = state.settings() else { return };
+  let smtp = match settings.profile().notifications.smtp.clone() {
+    Some(smtp) if smtp.enabled => smtp,
+    _ => return,
+  };
+  let now = Local::now();
+  if !digest::is_digest_due(&smtp, now, digest_log::last_sent(&settings.active_profile)) {
+    return;
+  }
+  // Mark the day as handled up front so a failing server is not retried every minute.
+  if let Err(e) = digest_log::record_sent(&settings.active_profile, now.date_naive()) {
+    println!("⚠️ Failed to record the usage digest: {}", e);
+  }
+
+  let currency = settings.profile().currency.clone();
+  let result = match SmtpNotifier::new(smtp.clone(), currency.clone()) {
+    Ok(notifier) => match digest::build_digest(state.repo(), smtp.digest, &currency).await {
+      Ok(digest) => notifier.send(&digest.subject, &digest.body).await,
+      Err(e) => Err(e),
+    },
+    Err(e) => Err(e),
+  };
+  if let Err(e) = result {
+    println!("❌ Usage digest failed: {}", e);
+  }
+}
+
+pub async fn fire_alert(app: &AppHandle, config: &NotificationConfig, currency: &CurrencyConfig, alert: &Alert) {
+  if let Some(until) = app.state::<AppState>().snoozed_until() {
+    println!("🔕 Alert '{}' snoozed until {}, not delivering", alert.title, until.format("%H:%M"));
+    return;
+  }
+  println!("🔔 Alert: {} - {}", alert.title, alert.message);
+  let _ = app.emit("alert-fired", alert);
+  app.state::<AppState>().events.publish(StreamEvent::Alerts(alert.clone()));
+
+  // The clip plays alongside delivery instead of holding up webhooks and email.
+  if config.sounds.enabled {
+    let (app, sounds, severity) = (app.clone(), config.sounds.clone(), alert.severity);
+    tauri::async_runtime::spawn(async move {
+      if let Err(e) = sound_player::play_for_severity(&app, &sounds, severity).await {
+        println!("⚠️ Alert sound failed: {}", e);
+      }
+    });
+  }
+
+  let notifiers = build_notifiers(config, currency);
+  for error in use_cases::dispatch_alert(&notifiers, alert).await {
+    println!("❌ Notification failed: {}", error);
+  }
+}
+
+pub fn build_notifiers(config: &NotificationConfig, currency: &CurrencyConfig) -> Vec<Arc<dyn Notifier + Send + Sync>> {
+  let mut notifiers: Vec<Arc<dyn Notifier + Send + Sync>> = Vec::new();
+  for webhook in config.webhooks.iter().filter(|webhook| webhook.enabled) {
+    match WebhookNotifier::new(webhook.clone()) {
+      Ok(notifier) => notifiers.push(Arc::new(notifier)),
+      Err(e) => println!("⚠️ Skipping webhook: {}", e),
+    }
+  }
+  if let Some(smtp) = config.smtp.as_ref().filter(|smtp| smtp.enabled && smtp.send_alerts) {
+    match SmtpNotifier::new(smtp.clone(), currency.clone()) {
+      Ok(notifier) => notifiers.push(Arc::new(notifier)),
+      Err(e) => println!("⚠️ Skipping SMTP channel: {}", e),
+    }
+  }
+  notifiers
+}
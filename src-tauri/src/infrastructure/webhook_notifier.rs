@@ -0,0 +1,307 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::DateTime;
+use serde_json::{json, Value};
+use crate::domain::{
+  entities::{Alert, AlertSeverity, WebhookConfig, WebhookPreset},
+  notifier::Notifier,
+};
+
+pub struct WebhookNotifier {
+  config: WebhookConfig,
+  client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+  pub fn new(config: WebhookConfig) -> Result<Self, String> {
+    if config.url.trim().is_empty() {
+      return Err(format!("Webhook '{}' has no URL", config.name));
+    }
+    let client = reqwest::Client::builder()
+      .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+      .build()
+      .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    Ok(Self { config, client })
+  }
+
+  pub fn render_payload(&self, alert: &Alert) -> Result<Value, String> {
+    let template = match self.config.preset {
+      WebhookPreset::Slack => slack_template(),
+      WebhookPreset::Discord => discord_template(),
+      WebhookPreset::Custom => self.config.payload_template.clone()
+        .ok_or_else(|| format!("Webhook '{}' uses the custom preset but has no payload template", self.config.name))?,
+    };
+    Ok(render_template(&template, &template_context(alert)?))
+  }
+
+  async fn post_once(&self, payload: &Value) -> Result<(), (bool, String)> {
+    let mut request = self.client.post(&self.config.url).json(payload);
+    for (name, value) in &self.config.headers {
+      request = request.header(name.as_str(), value.as_str());
+    }
+
+    let response = request.send().await.map_err(|e| (true, format!("request failed: {}", e)))?;
+    let status = response.status();
+    if status.is_success() {
+      return Ok(());
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    let retryable = status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+    Err((retryable, format!("HTTP {}: {}", status, body.trim())))
+  }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+  fn name(&self) -> String {
+    format!("webhook:{}", self.config.name)
+  }
+
+  async fn notify(&self, alert: &Alert) -> Result<(), String> {
+    if !self.config.enabled || alert.severity < self.config.min_severity {
+      return Ok(());
+    }
+
+    let payload = self.render_payload(alert)?;
+    let mut attempt = 0;
+    loop {
+      match self.post_once(&payload).await {
+        Ok(()) => {
+          println!("✅ Webhook '{}' delivered: {}", self.config.name, alert.title);
+          return Ok(());
+        }
+        Err((retryable, error)) => {
+          if !retryable || attempt >= self.config.max_retries {
+            return Err(format!("delivery failed after {} attempt(s): {}", attempt + 1, error));
+          }
+          let backoff = Duration::from_millis(500 * 2u64.pow(attempt.min(6)));
+          println!("⚠️ Webhook '{}' attempt {} failed ({}), retrying in {:?}", self.config.name, attempt + 1, error, backoff);
+          tokio::time::sleep(backoff).await;
+          attempt += 1;
+        }
+      }
+    }
+  }
+}
+
+fn severity_color(severity: AlertSeverity) -> (&'static str, u32) {
+  match severity {
+    AlertSeverity::Info => ("#228be6", 0x228be6),
+    AlertSeverity::Warning => ("#ffa726", 0xffa726),
+    AlertSeverity::Critical => ("#ff4757", 0xff4757),
+  }
+}
+
+fn template_context(alert: &Alert) -> Result<Value, String> {
+  let mut context = serde_json::to_value(alert).map_err(|e| format!("Failed to serialize alert: {}", e))?;
+  let (color, color_int) = severity_color(alert.severity);
+  // Slack wants `ts` in epoch seconds rather than RFC 3339.
+  let timestamp_unix = DateTime::parse_from_rfc3339(&alert.timestamp).ok().map(|time| time.timestamp());
+  if let Value::Object(map) = &mut context {
+    map.insert("color".to_string(), json!(color));
+    map.insert("color_int".to_string(), json!(color_int));
+    map.insert("timestamp_unix".to_string(), json!(timestamp_unix));
+  }
+  Ok(context)
+}
+
+fn slack_template() -> Value {
+  json!({
+    "text": "*{{title}}*\n{{message}}",
+    "attachments": [{
+      "color": "{{color}}",
+      "fields": [
+        { "title": "Session cost", "value": "${{stats.session_cost}}", "short": true },
+        { "title": "Today", "value": "${{stats.cost}}", "short": true },
+        { "title": "Burn rate", "value": "{{stats.burn_rate}} tokens/min", "short": true },
+        { "title": "Model", "value": "{{stats.model}}", "short": true },
+        { "title": "Triggered by", "value": "{{trigger_field}} = {{trigger_value}} (threshold {{threshold}})", "short": false }
+      ],
+      "ts": "{{timestamp_unix}}"
+    }]
+  })
+}
+
+fn discord_template() -> Value {
+  json!({
+    "content": "**{{title}}**",
+    "embeds": [{
+      "title": "{{title}}",
+      "description": "{{message}}",
+      "color": "{{color_int}}",
+      "fields": [
+        { "name": "Session cost", "value": "${{stats.session_cost}}", "inline": true },
+        { "name": "Today", "value": "${{stats.cost}}", "inline": true },
+        { "name": "Burn rate", "value": "{{stats.burn_rate}} tokens/min", "inline": true },
+        { "name": "Model", "value": "{{stats.model}}", "inline": true },
+        { "name": "Triggered by", "value": "{{trigger_field}} = {{trigger_value}} (threshold {{threshold}})", "inline": false }
+      ],
+      "timestamp": "{{timestamp}}"
+    }]
+  })
+}
+
+/// Replaces `{{path}}` placeholders in every string of the template.
+/// A string that consists of a single placeholder is replaced by the raw
+/// JSON value, so numbers and objects (e.g. `"{{stats}}"`) keep their type.
+pub fn render_template(template: &Value, context: &Value) -> Value {
+  match template {
+    Value::String(text) => render_string(text, context),
+    Value::Array(items) => Value::Array(items.iter().map(|item| render_template(item, context)).collect()),
+    Value::Object(map) => Value::Object(
+      map.iter().map(|(key, value)| (key.clone(), render_template(value, context))).collect()
+    ),
+    other => other.clone(),
+  }
+}
+
+fn render_string(text: &str, context: &Value) -> Value {
+  let trimmed = text.trim();
+  if trimmed.starts_with("{{") && trimmed.ends_with("}}") && trimmed.matches("{{").count() == 1 {
+    let path = trimmed[2..trimmed.len() - 2].trim();
+    if let Some(value) = lookup(context, path) {
+      return round_numbers(value);
+    }
+  }
+
+  let mut output = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find("{{") {
+    output.push_str(&rest[..start]);
+    match rest[start + 2..].find("}}") {
+      Some(end) => {
+        let path = rest[start + 2..start + 2 + end].trim();
+        match lookup(context, path) {
+          Some(value) => output.push_str(&display_value(value)),
+          None => output.push_str(&rest[start..start + 4 + end]),
+        }
+        rest = &rest[start + 4 + end..];
+      }
+      None => {
+        output.push_str(&rest[start..]);
+        rest = "";
+      }
+    }
+  }
+  output.push_str(rest);
+  Value::String(output)
+}
+
+fn lookup<'a>(context: &'a Value, path: &str) -> Option<&'a Value> {
+  path.split('.').try_fold(context, |value, key| value.get(key))
+}
+
+fn display_value(value: &Value) -> String {
+  match value {
+    Value::String(s) => s.clone(),
+    Value::Null => "n/a".to_string(),
+    Value::Number(n) if n.is_f64() => format!("{:.2}", n.as_f64().unwrap_or_default()),
+    other => other.to_string(),
+  }
+}
+
+// f32 fields widen to f64 on serialization; trim the float noise.
+fn round_numbers(value: &Value) -> Value {
+  match value {
+    Value::Number(n) if n.is_f64() => {
+      let rounded = (n.as_f64().unwrap_or_default() * 10_000.0).round() / 10_000.0;
+      json!(rounded)
+    }
+    Value::Array(items) => Value::Array(items.iter().map(round_numbers).collect()),
+    Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), round_numbers(v))).collect()),
+    other => other.clone(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::TcpListener;
+  use crate::application::alerts::build_alert;
+  use crate::domain::entities::{AlertKind, UsageStats};
+  use super::*;
+
+  fn alert() -> Alert {
+    build_alert(
+      AlertKind::BudgetWarning,
+      AlertSeverity::Warning,
+      "Daily budget at 85%".to_string(),
+      "Spent $8.50 of $10.00".to_string(),
+      "cost",
+      8.5,
+      8.0,
+      &UsageStats::default(),
+    )
+  }
+
+  /// Answers each request with the next status, repeating the last one.
+  async fn stub_server(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    tokio::spawn(async move {
+      loop {
+        let Ok((mut socket, _)) = listener.accept().await else { return };
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 4096];
+        while !request_complete(&request) {
+          match socket.read(&mut buffer).await {
+            Ok(0) | Err(_) => break,
+            Ok(read) => request.extend_from_slice(&buffer[..read]),
+          }
+        }
+        let index = counter.fetch_add(1, Ordering::SeqCst);
+        let status = statuses[index.min(statuses.len() - 1)];
+        let response = format!("HTTP/1.1 {} Stub\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+        let _ = socket.write_all(response.as_bytes()).await;
+      }
+    });
+    (url, requests)
+  }
+
+  fn request_complete(request: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(request);
+    let Some(header_end) = text.find("\r\n\r\n") else { return false };
+    let length = text[..header_end].lines()
+      .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length:").map(|value| value.trim().parse::<usize>().unwrap_or(0)))
+      .unwrap_or(0);
+    request.len() >= header_end + 4 + length
+  }
+
+  fn notifier(url: String, max_retries: u32) -> WebhookNotifier {
+    WebhookNotifier::new(WebhookConfig { url, max_retries, ..WebhookConfig::default() }).unwrap()
+  }
+
+  #[tokio::test]
+  async fn retries_server_errors_until_delivered() {
+    let (url, requests) = stub_server(vec![503, 200]).await;
+    notifier(url, 2).notify(&alert()).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+  }
+
+  #[tokio::test]
+  async fn gives_up_after_max_retries() {
+    let (url, requests) = stub_server(vec![503]).await;
+    let error = notifier(url, 1).notify(&alert()).await.unwrap_err();
+    assert!(error.contains("after 2 attempt(s)"), "{}", error);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+  }
+
+  #[tokio::test]
+  async fn does_not_retry_client_errors() {
+    let (url, requests) = stub_server(vec![400]).await;
+    assert!(notifier(url, 3).notify(&alert()).await.is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+  }
+
+  #[test]
+  fn slack_ts_is_epoch_seconds() {
+    let alert = alert();
+    let payload = notifier("http://127.0.0.1/hook".to_string(), 0).render_payload(&alert).unwrap();
+    let expected = DateTime::parse_from_rfc3339(&alert.timestamp).unwrap().timestamp();
+    assert_eq!(payload["attachments"][0]["ts"], json!(expected));
+  }
+}
@@ -0,0 +1,302 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, WebviewWindow, WindowEvent};
+use crate::domain::settings::AutoHide;
+use super::{placement::{self, Origin}, tauri_commands::AppState};
+
+pub const MAIN_LABEL: &str = "main";
+/// Focus flickers while the popup is shown or dragged; a blur only hides
+/// the popup once it has lasted this long.
+const BLUR_DEBOUNCE: Duration = Duration::from_millis(150);
+/// Clicking the tray icon blurs the popup before the click arrives. A toggle
+/// this soon after a blur hid the popup is that same click, so it must not
+/// reopen it.
+const REOPEN_GUARD: Duration = Duration::from_millis(300);
+/// How often the pointer is sampled for the idle and pointer-leave modes.
+const POINTER_POLL: Duration = Duration::from_millis(250);
+/// The pointer must stay off the popup this long, so brushing past the edge doesn't hide it.
+const LEAVE_DEBOUNCE: Duration = Duration::from_millis(400);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+  Hidden,
+  Visible,
+  /// Lost focus; hides when the debounce elapses unless focus comes back.
+  BlurPending,
+}
+
+#[derive(Debug, Clone)]
+enum Event {
+  Show(Origin),
+  Hide,
+  Toggle(Origin),
+  FocusLost,
+  FocusGained,
+  /// The debounce timer scheduled for this generation fired.
+  BlurElapsed(u64),
+  /// The pointer watcher for this showing decided the popup should hide.
+  AutoHide(u64),
+  SetPinned(bool),
+}
+
+#[derive(Debug)]
+enum Effect {
+  None,
+  /// Show for the given showing, which the pointer watcher follows.
+  Show(Origin, u64),
+  Hide,
+  ScheduleBlurHide(u64),
+}
+
+struct Machine {
+  visibility: Visibility,
+  /// Bumped on every transition so stale debounce timers are ignored.
+  generation: u64,
+  /// Bumped each time the popup is shown; stays put while it is open.
+  showing: u64,
+  blur_hidden_at: Option<Instant>,
+  pinned: bool,
+}
+
+impl Machine {
+  const fn new() -> Self {
+    Self { visibility: Visibility::Hidden, generation: 0, showing: 0, blur_hidden_at: None, pinned: false }
+  }
+
+  fn transition(&mut self, event: Event, now: Instant, auto_hide: AutoHide) -> Effect {
+    match (self.visibility, event) {
+      (_, Event::Show(origin)) => self.enter_visible(origin),
+      (Visibility::Hidden, Event::Hide) => Effect::None,
+      (_, Event::Hide) => self.enter_hidden(None),
+      (Visibility::Hidden, Event::Toggle(origin)) => {
+        if self.blur_hidden_at.take().is_some_and(|at| now.duration_since(at) < REOPEN_GUARD) {
+          Effect::None
+        } else {
+          self.enter_visible(origin)
+        }
+      }
+      // With a blur pending, the toggle is the tray click that caused it.
+      (_, Event::Toggle(_)) => self.enter_hidden(None),
+      (Visibility::Visible, Event::FocusLost) if !self.pinned && auto_hide == AutoHide::OnBlur => {
+        self.visibility = Visibility::BlurPending;
+        self.generation += 1;
+        Effect::ScheduleBlurHide(self.generation)
+      }
+      (Visibility::BlurPending, Event::FocusGained) => {
+        self.visibility = Visibility::Visible;
+        self.generation += 1;
+        Effect::None
+      }
+      (Visibility::BlurPending, Event::BlurElapsed(generation)) if generation == self.generation => {
+        self.enter_hidden(Some(now))
+      }
+      (Visibility::Hidden, Event::AutoHide(_)) => Effect::None,
+      (_, Event::AutoHide(showing)) if showing == self.showing && !self.pinned => self.enter_hidden(None),
+      (visibility, Event::SetPinned(pinned)) => {
+        self.pinned = pinned;
+        if pinned && visibility == Visibility::BlurPending {
+          self.visibility = Visibility::Visible;
+          self.generation += 1;
+        }
+        Effect::None
+      }
+      _ => Effect::None,
+    }
+  }
+
+  fn enter_visible(&mut self, origin: Origin) -> Effect {
+    self.visibility = Visibility::Visible;
+    self.generation += 1;
+    self.showing += 1;
+    self.blur_hidden_at = None;
+    Effect::Show(origin, self.showing)
+  }
+
+  fn enter_hidden(&mut self, blur_hidden_at: Option<Instant>) -> Effect {
+    self.visibility = Visibility::Hidden;
+    self.generation += 1;
+    self.blur_hidden_at = blur_hidden_at;
+    Effect::Hide
+  }
+}
+
+static MACHINE: Mutex<Machine> = Mutex::new(Machine::new());
+
+pub fn visibility() -> Visibility {
+  MACHINE.lock().unwrap().visibility
+}
+
+pub fn is_pinned() -> bool {
+  MACHINE.lock().unwrap().pinned
+}
+
+/// Pins or unpins the popup; while pinned it only hides on request.
+pub fn set_pinned(app: &AppHandle, pinned: bool) -> Result<bool, String> {
+  dispatch(app, Event::SetPinned(pinned))?;
+  println!("📌 Popup {}", if pinned { "pinned" } else { "unpinned" });
+  let _ = app.emit("popup-pinned", pinned);
+  Ok(pinned)
+}
+
+pub fn toggle_pinned(app: &AppHandle) -> Result<bool, String> {
+  set_pinned(app, !is_pinned())
+}
+
+/// Places the popup for `origin`, shows it and gives it focus.
+pub fn show(app: &AppHandle, origin: Origin) -> Result<(), String> {
+  dispatch(app, Event::Show(origin))
+}
+
+pub fn hide(app: &AppHandle) -> Result<(), String> {
+  dispatch(app, Event::Hide)
+}
+
+/// Shows or hides the popup, returning whether it is now visible.
+pub fn toggle(app: &AppHandle, origin: Origin) -> Result<bool, String> {
+  dispatch(app, Event::Toggle(origin))?;
+  Ok(visibility() != Visibility::Hidden)
+}
+
+/// Routes the popup's window events through the state machine.
+pub fn attach(window: &WebviewWindow) {
+  let window_clone = window.clone();
+  window.on_window_event(move |event| {
+    let app = window_clone.app_handle();
+    let result = match event {
+      WindowEvent::Moved(position) => {
+        placement::record_move(&window_clone, *position);
+        Ok(())
+      }
+      WindowEvent::CloseRequested { api, .. } => {
+        api.prevent_close();
+        dispatch(app, Event::Hide)
+      }
+      WindowEvent::Focused(true) => dispatch(app, Event::FocusGained),
+      WindowEvent::Focused(false) => dispatch(app, Event::FocusLost),
+      _ => Ok(()),
+    };
+    if let Err(e) = result {
+      println!("⚠️ Popup window event failed: {}", e);
+    }
+  });
+}
+
+/// Runs a transition, then its effect. The lock is released first: window
+/// calls may wait on the main thread, which could be delivering a focus
+/// event that needs the lock.
+fn dispatch(app: &AppHandle, event: Event) -> Result<(), String> {
+  let auto_hide = auto_hide_mode(app);
+  let effect = MACHINE.lock().unwrap().transition(event, Instant::now(), auto_hide);
+  let result = apply(app, effect);
+  if result.is_err() {
+    // Keep the machine in line with a window that failed to show or hide.
+    let visible = app.get_webview_window(MAIN_LABEL).is_some_and(|window| window.is_visible().unwrap_or(false));
+    let mut machine = MACHINE.lock().unwrap();
+    machine.visibility = if visible { Visibility::Visible } else { Visibility::Hidden };
+    machine.generation += 1;
+  }
+  result
+}
+
+fn apply(app: &AppHandle, effect: Effect) -> Result<(), String> {
+  match effect {
+    Effect::None => Ok(()),
+    Effect::Show(origin, showing) => {
+      let window = main_window(app)?;
+      let window_settings = app.state::<AppState>().settings()?.window;
+      if let Err(e) = placement::place_popup(&window, &window_settings, origin) {
+        println!("⚠️ Failed to place popup: {}", e);
+      }
+      let _ = window.unminimize();
+      window.show().map_err(|e| format!("Failed to show popup: {}", e))?;
+      let _ = window.set_always_on_top(true);
+      let _ = window.set_focus();
+      watch_pointer(app, showing);
+      println!("🪟 Popup shown");
+      Ok(())
+    }
+    Effect::Hide => {
+      main_window(app)?.hide().map_err(|e| format!("Failed to hide popup: {}", e))?;
+      println!("🪟 Popup hidden");
+      Ok(())
+    }
+    Effect::ScheduleBlurHide(generation) => {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(BLUR_DEBOUNCE).await;
+        if let Err(e) = dispatch(&app, Event::BlurElapsed(generation)) {
+          println!("⚠️ Failed to hide popup on blur: {}", e);
+        }
+      });
+      Ok(())
+    }
+  }
+}
+
+/// Samples the pointer while this showing lasts and hides the popup in the
+/// idle and pointer-leave modes. It re-reads the mode on every tick, so a
+/// settings change applies to a popup that is already open.
+fn watch_pointer(app: &AppHandle, showing: u64) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let mut last_pointer: Option<PhysicalPosition<f64>> = None;
+    let mut last_activity = Instant::now();
+    let mut entered = false;
+    let mut left_at: Option<Instant> = None;
+    loop {
+      tokio::time::sleep(POINTER_POLL).await;
+      {
+        let machine = MACHINE.lock().unwrap();
+        if machine.showing != showing || machine.visibility == Visibility::Hidden {
+          return;
+        }
+        if machine.pinned {
+          last_activity = Instant::now();
+          left_at = None;
+          continue;
+        }
+      }
+      let Ok(window) = main_window(&app) else { return };
+      let pointer = app.cursor_position().ok();
+      let over = pointer.is_some_and(|pointer| is_over(&window, pointer));
+      let now = Instant::now();
+      if over && pointer != last_pointer {
+        last_activity = now;
+      }
+      last_pointer = pointer;
+      if over {
+        entered = true;
+        left_at = None;
+      } else if entered {
+        left_at.get_or_insert(now);
+      }
+
+      let settings = app.state::<AppState>().settings().map(|settings| settings.window).unwrap_or_default();
+      let hide = match settings.auto_hide {
+        AutoHide::Idle => now.duration_since(last_activity) >= Duration::from_secs(settings.auto_hide_idle_secs as u64),
+        AutoHide::PointerLeave => left_at.is_some_and(|at| now.duration_since(at) >= LEAVE_DEBOUNCE),
+        AutoHide::OnBlur | AutoHide::Never => false,
+      };
+      if hide {
+        if let Err(e) = dispatch(&app, Event::AutoHide(showing)) {
+          println!("⚠️ Failed to auto-hide popup: {}", e);
+        }
+        return;
+      }
+    }
+  });
+}
+
+fn is_over(window: &WebviewWindow, pointer: PhysicalPosition<f64>) -> bool {
+  let (Ok(position), Ok(size)) = (window.outer_position(), window.outer_size()) else { return false };
+  pointer.x >= position.x as f64 && pointer.x < position.x as f64 + size.width as f64
+    && pointer.y >= position.y as f64 && pointer.y < position.y as f64 + size.height as f64
+}
+
+fn auto_hide_mode(app: &AppHandle) -> AutoHide {
+  app.state::<AppState>().settings().map(|settings| settings.window.auto_hide).unwrap_or_default()
+}
+
+fn main_window(app: &AppHandle) -> Result<WebviewWindow, String> {
+  app.get_webview_window(MAIN_LABEL).ok_or_else(|| "Main window not found".to_string())
+}
@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use super::{atomic_file, settings_store::APP_IDENTIFIER};
+
+const WINDOW_STATE_FILE: &str = "window-state.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SavedPosition {
+  /// Physical pixels in desktop coordinates.
+  pub x: i32,
+  pub y: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SavedGeometry {
+  /// Logical pixels in desktop coordinates.
+  pub x: f64,
+  pub y: f64,
+  pub width: f64,
+  pub height: f64,
+}
+
+/// Window geometry remembered between runs. Kept apart from `settings.json`
+/// so dragging a window never touches the user's hand-edited settings.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct WindowState {
+  /// Popup positions keyed by monitor setup, see `placement::monitor_setup_key`.
+  pub popup_positions: BTreeMap<String, SavedPosition>,
+  pub mini: Option<SavedGeometry>,
+  /// Whether the mini widget was open at exit, so it comes back on launch.
+  pub mini_open: bool,
+}
+
+pub struct WindowStateStore {
+  path: PathBuf,
+}
+
+impl WindowStateStore {
+  pub fn new(path: PathBuf) -> Self {
+    Self { path }
+  }
+
+  pub fn default_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir().ok_or("Could not determine the platform config directory")?;
+    Ok(config_dir.join(APP_IDENTIFIER).join(WINDOW_STATE_FILE))
+  }
+
+  /// Loads the saved state; a missing or unreadable file starts fresh.
+  pub fn load(&self) -> WindowState {
+    fs::read_to_string(&self.path)
+      .ok()
+      .and_then(|contents| serde_json::from_str(&contents).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn save(&self, state: &WindowState) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(state).map_err(|e| format!("Failed to serialize window state: {}", e))?;
+    atomic_file::write(&self.path, &json)
+  }
+}
@@ -3,79 +3,139 @@
   windows_subsystem = "windows"
 )]
 
-use std::sync::Arc;
-use tauri::{Manager, WindowEvent};
+#[cfg(feature = "gui")]
+use std::sync::{Arc, Mutex, RwLock};
+#[cfg(feature = "gui")]
+use tauri::Manager;
 
 pub mod domain;
 pub mod infrastructure;
 pub mod application;
+pub mod cli;
 
+#[cfg(feature = "gui")]
+use domain::repository::UsageRepository;
+#[cfg(feature = "gui")]
 use infrastructure::{
   ccusage_repository::CcusageRepository,
+  local_api,
+  policy_store,
+  settings_store::SettingsStore,
+  settings_watcher,
+  shortcut,
   tauri_commands::{self, AppState},
+  mini_window,
   tray,
+  usage_monitor,
+  window_manager,
+  window_state_store::WindowStateStore,
 };
 
+#[cfg(feature = "gui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
     .plugin(tauri_plugin_shell::init())
+    .plugin(tauri_plugin_clipboard_manager::init())
+    .plugin(tauri_plugin_opener::init())
+    .plugin(tauri_plugin_global_shortcut::Builder::new().with_handler(shortcut::handle).build())
     .invoke_handler(tauri::generate_handler![
       tauri_commands::get_claude_usage,
       tauri_commands::get_usage_summary,
+      tauri_commands::get_usage_history,
+      tauri_commands::get_model_breakdown,
+      tauri_commands::get_project_breakdown,
+      tauri_commands::compare_periods,
+      tauri_commands::show_main_window,
       tauri_commands::hide_main_window,
       tauri_commands::toggle_main_window,
+      tauri_commands::toggle_popup_pin,
+      tauri_commands::is_popup_pinned,
+      tauri_commands::get_shortcut_status,
       tauri_commands::request_permissions,
-      tauri_commands::emergency_show_window,
+      tauri_commands::reset_popup_position,
+      tauri_commands::toggle_mini_widget,
+      tauri_commands::open_dashboard,
       tauri_commands::show_system_notification,
       tauri_commands::play_warning_sound,
+      tauri_commands::play_alert_sound,
+      tauri_commands::get_settings,
+      tauri_commands::update_settings,
+      tauri_commands::get_effective_settings,
+      tauri_commands::get_policy,
+      tauri_commands::switch_profile,
+      tauri_commands::get_monitor_status,
+      tauri_commands::set_monitoring_paused,
+      tauri_commands::snooze_alerts,
+      tauri_commands::get_plan,
+      tauri_commands::update_plan,
+      tauri_commands::get_plan_limits,
+      tauri_commands::get_detected_block_limit,
+      tauri_commands::get_notification_config,
+      tauri_commands::update_notification_config,
+      tauri_commands::test_webhook,
+      tauri_commands::test_smtp,
+      tauri_commands::send_usage_digest,
     ])
     .setup(|app| {
-            let usage_repo = Arc::new(CcusageRepository::new());
-      let app_state = AppState { usage_repo };
+      let settings_store = SettingsStore::new(SettingsStore::default_path()?);
+      let settings = settings_store.load().unwrap_or_else(|e| {
+        println!("⚠️ Failed to load settings, using defaults: {}", e);
+        Default::default()
+      });
+      println!("⚙️ Settings file: {}", settings_store.path().display());
+      let usage_repo: Arc<dyn UsageRepository + Send + Sync> =
+        Arc::new(CcusageRepository::with_data_roots(settings.profile().expanded_data_roots()));
+      let settings_path = settings_store.path().to_path_buf();
+      let window_state_store = WindowStateStore::new(WindowStateStore::default_path()?);
+      let policy = policy_store::load_policy();
+      // A policy that is there but does not load fails closed rather than
+      // letting user settings run unchecked.
+      if let Some(e) = &policy.error {
+        println!("❌ {}", e);
+        return Err(format!("Refusing to start without the organization policy: {}", e).into());
+      }
+      if let Some(source) = &policy.source {
+        println!("🔒 Loaded organization policy from {}", source.display());
+      }
+      let app_state = AppState {
+        usage_repo: RwLock::new(usage_repo),
+        repo_generation: Default::default(),
+        settings: Mutex::new(settings),
+        settings_store,
+        policy,
+        detected_limit: Mutex::new(None),
+        latest_usage: Mutex::new(None),
+        latest_usage_at: Mutex::new(None),
+        summary_cache: Default::default(),
+        blocks_cache: Mutex::new(None),
+        daily_cache: Mutex::new(None),
+        daily_fetch: tokio::sync::Mutex::new(()),
+        events: Default::default(),
+        monitor_wakeup: Default::default(),
+        monitoring_paused: Default::default(),
+        alerts_snoozed_until: Mutex::new(None),
+        window_state: Mutex::new(window_state_store.load()),
+        window_state_store,
+        shortcut_status: Default::default(),
+      };
 
       app.manage(app_state);
+      usage_monitor::start(app.handle().clone());
+      if let Err(e) = settings_watcher::start(app.handle().clone(), settings_path) {
+        println!("⚠️ Settings hot-reload disabled: {}", e);
+      }
 
       match tray::create_tray(&app.handle()) {
         Ok(_) => {},
         Err(e) => return Err(e),
       }
+      mini_window::restore(app.handle());
+      shortcut::apply(app.handle());
+      local_api::apply(app.handle());
 
-      let main_window = app.get_webview_window("main").unwrap();
-      let window_clone = main_window.clone();
-      main_window.on_window_event(move |event| {
-        match event {
-          WindowEvent::CloseRequested { api, .. } => {
-            api.prevent_close();
-            let _ = window_clone.hide();
-          }
-          WindowEvent::Focused(focused) => {
-            if !focused {
-              println!("🔍 Window lost focus, checking if should hide...");
-              let window_for_hide = window_clone.clone();
-              std::thread::spawn(move || {
-                std::thread::sleep(std::time::Duration::from_millis(100));
-                // Double check if window is still unfocused and visible
-                if let Ok(is_focused) = window_for_hide.is_focused() {
-                  if !is_focused {
-                    if let Ok(is_visible) = window_for_hide.is_visible() {
-                      if is_visible {
-                        println!("🔍 Confirmed: Window lost focus, hiding now");
-                        let _ = window_for_hide.hide();
-                      }
-                    }
-                  } else {
-                    println!("🔍 Window regained focus, not hiding");
-                  }
-                }
-              });
-            } else {
-              println!("🔍 Window gained focus");
-            }
-          }
-          _ => {}
-        }
-      });
+      let main_window = app.get_webview_window(window_manager::MAIN_LABEL).unwrap();
+      window_manager::attach(&main_window);
       Ok(())
     })
     .run(tauri::generate_context!())
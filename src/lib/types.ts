@@ -2,10 +2,16 @@ export interface UsageStats {
   active_session: boolean;
   current_tokens: number;
   daily_tokens: number;
+  weekly_tokens: number;
   cost: number;
   model: string;
   session_cost: number;
   burn_rate: number | null;
+  block_start_time: string | null;
+  block_end_time: string | null;
+  block_minutes_remaining: number | null;
+  percent_of_block_limit: number | null;
+  percent_of_weekly_limit: number | null;
 }
 
 export interface UsageSummary {
@@ -21,6 +27,177 @@ export interface UsageSummary {
 
 export type Period = 'day' | 'week' | 'month';
 
+export type DashboardPeriod = Period | '30d' | '90d';
+
+export interface TokenBreakdown {
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+}
+
+export interface HistoryPoint {
+  date: string;
+  tokens: TokenBreakdown;
+  total_tokens: number;
+  cost_usd: number;
+}
+
+export interface UsageHistory {
+  period: DashboardPeriod;
+  start_date: string;
+  end_date: string;
+  points: HistoryPoint[];
+  total_tokens: number;
+  total_cost_usd: number;
+  peak_date: string | null;
+}
+
+export interface BreakdownEntry {
+  name: string;
+  key: string;
+  tokens: TokenBreakdown;
+  total_tokens: number;
+  cost_usd: number;
+  cost_share: number;
+  count: number;
+}
+
+export interface UsageBreakdown {
+  period: DashboardPeriod;
+  start_date: string;
+  end_date: string;
+  total_tokens: number;
+  total_cost_usd: number;
+  entries: BreakdownEntry[];
+}
+
+export interface PeriodTotals {
+  start_date: string;
+  end_date: string;
+  days: number;
+  tokens: TokenBreakdown;
+  total_tokens: number;
+  cost_usd: number;
+  avg_cost_per_day: number;
+}
+
+export interface PeriodComparison {
+  period: DashboardPeriod;
+  current: PeriodTotals;
+  previous: PeriodTotals;
+  tokens_change_percent: number | null;
+  cost_change_percent: number | null;
+}
+
+export type Plan = 'subscription' | 'api';
+
+export interface PlanConfig {
+  plan: Plan;
+  block_token_limit: number | null;
+  weekly_token_limit: number | null;
+  auto_detect_block_limit: boolean;
+}
+
+export type LimitConfidence = 'low' | 'medium' | 'high';
+
+export interface DetectedBlockLimit {
+  block_tokens: number;
+  confidence: LimitConfidence;
+  completed_blocks: number;
+  limit_hits: number;
+  max_block_start_time: string;
+}
+
+export interface PlanLimits {
+  block_tokens: number | null;
+  weekly_tokens: number | null;
+  block_detected: boolean;
+}
+
+export type AlertSeverity = 'info' | 'warning' | 'critical';
+
+export type AlertKind =
+  | 'budget_warning'
+  | 'budget_exceeded'
+  | 'runaway_session'
+  | 'block_started'
+  | 'block_ending_soon'
+  | 'block_reset';
+
+export interface Alert {
+  kind: AlertKind;
+  severity: AlertSeverity;
+  title: string;
+  message: string;
+  trigger_field: string;
+  trigger_value: number;
+  threshold: number;
+  stats: UsageStats;
+  timestamp: string;
+}
+
+export interface AlertRules {
+  daily_budget_usd: number | null;
+  weekly_budget_usd: number | null;
+  monthly_budget_usd: number | null;
+  block_budget_usd: number | null;
+  budget_warning_percent: number;
+  runaway_burn_rate: number | null;
+  block_start_alerts: boolean;
+  block_ending_minutes: number | null;
+  block_reset_alerts: boolean;
+}
+
+export type WebhookPreset = 'slack' | 'discord' | 'custom';
+
+export interface WebhookConfig {
+  name: string;
+  url: string;
+  preset: WebhookPreset;
+  payload_template: unknown | null;
+  headers: Record<string, string>;
+  min_severity: AlertSeverity;
+  timeout_secs: number;
+  max_retries: number;
+  enabled: boolean;
+}
+
+export type SmtpSecurity = 'none' | 'starttls' | 'tls';
+
+export type DigestSchedule = 'off' | 'daily' | 'weekly';
+
+export interface SmtpConfig {
+  enabled: boolean;
+  host: string;
+  port: number;
+  security: SmtpSecurity;
+  username: string | null;
+  password: string | null;
+  from: string;
+  to: string[];
+  timeout_secs: number;
+  send_alerts: boolean;
+  min_severity: AlertSeverity;
+  digest: DigestSchedule;
+  digest_hour: number;
+  digest_weekday: number;
+}
+
+export interface SoundConfig {
+  enabled: boolean;
+  info: string | null;
+  warning: string | null;
+  critical: string | null;
+}
+
+export interface NotificationConfig {
+  rules: AlertRules;
+  webhooks: WebhookConfig[];
+  smtp: SmtpConfig | null;
+  sounds: SoundConfig;
+}
+
 export type WarningLevel = 'safe' | 'warning' | 'critical' | 'danger';
 
 export interface UsagePattern {
@@ -30,3 +207,116 @@ export interface UsagePattern {
   maxDailyUsage: number;
   consistencyScore: number;
 }
+
+export interface WarningThresholds {
+  warning: number;
+  critical: number;
+  danger: number;
+}
+
+export type PopupAnchor = 'tray' | 'top_right' | 'bottom_right' | 'near_cursor';
+
+export type AutoHide = 'on_blur' | 'idle' | 'pointer_leave' | 'never';
+
+export interface WindowSettings {
+  width: number;
+  height: number;
+  anchor: PopupAnchor;
+  remember_position: boolean;
+  auto_hide: AutoHide;
+  auto_hide_idle_secs: number;
+}
+
+export interface ShortcutSettings {
+  enabled: boolean;
+  accelerator: string;
+}
+
+export interface ShortcutStatus {
+  accelerator: string | null;
+  registered: boolean;
+  error: string | null;
+}
+
+export interface ApiSettings {
+  enabled: boolean;
+  port: number;
+  token: string | null;
+}
+
+export interface MetricsSettings {
+  endpoint: boolean;
+  textfile: string | null;
+}
+
+export type StreamTopic = 'usage' | 'alerts' | 'blocks';
+
+export type BudgetState = 'ok' | 'warning' | 'exceeded';
+
+export interface BudgetStatus {
+  period: 'day' | 'block' | 'week' | 'month';
+  limit_usd: number;
+  spent_usd: number;
+  remaining_usd: number;
+  percent: number;
+  state: BudgetState;
+}
+
+export interface MonitorStatus {
+  paused: boolean;
+  alerts_snoozed_until: string | null;
+}
+
+export type TrayIconStyle = 'ring' | 'bar';
+
+export type TrayGaugeMetric = 'block' | 'budget';
+
+export interface TraySettings {
+  icon_style: TrayIconStyle;
+  gauge_metric: TrayGaugeMetric;
+  monochrome: boolean;
+  tooltip_template: string;
+  title_template: string;
+}
+
+export interface CurrencyConfig {
+  code: string;
+  symbol: string;
+  usd_rate: number;
+}
+
+export interface Profile {
+  data_roots: string[];
+  plan: PlanConfig;
+  currency: CurrencyConfig;
+  notifications: NotificationConfig;
+}
+
+export interface AppSettings {
+  version: number;
+  refresh_interval_secs: number;
+  poll_interval_secs: number;
+  window: WindowSettings;
+  thresholds: WarningThresholds;
+  tray: TraySettings;
+  shortcut: ShortcutSettings;
+  api: ApiSettings;
+  metrics: MetricsSettings;
+  default_period: Period;
+  active_profile: string;
+  profiles: Record<string, Profile>;
+}
+
+export type LockRule = 'maximum' | 'minimum' | 'fixed';
+
+export interface LockedSetting {
+  path: string;
+  rule: LockRule;
+  value: unknown;
+}
+
+export interface PolicyStatus {
+  source: string | null;
+  error: string | null;
+  locked: LockedSetting[];
+}
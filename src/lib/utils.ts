@@ -1,4 +1,4 @@
-import type { Period, UsageStats, UsageSummary } from './types';
+import type { Period, UsageStats, UsageSummary, WarningThresholds } from './types';
 
 export function formatTokens(tokens: number): string {
   return tokens.toLocaleString();
@@ -8,6 +8,19 @@ export function formatCost(cost: number): string {
   return `$${cost.toFixed(3)}`;
 }
 
+export function formatCompactTokens(tokens: number): string {
+  if (tokens >= 1_000_000) return `${(tokens / 1_000_000).toFixed(1)}M`;
+  if (tokens >= 1_000) return `${(tokens / 1_000).toFixed(1)}k`;
+  return tokens.toString();
+}
+
+export function formatTimeLeft(minutes: number): string {
+  if (minutes >= 60) {
+    return `${Math.floor(minutes / 60)}h ${String(minutes % 60).padStart(2, '0')}m`;
+  }
+  return `${minutes}m`;
+}
+
 export function getPeriodTokens(period: Period, usageStats: UsageStats | null, summary: UsageSummary | null): number {
   if (!usageStats) return 0;
   if (period === 'day') {
@@ -66,10 +79,13 @@ export function getUsagePercentage(period: Period, usageStats: UsageStats | null
 
 export type WarningLevel = 'safe' | 'warning' | 'critical' | 'danger';
 
-export function getWarningLevel(percentage: number): WarningLevel {
-  if (percentage >= 95) return 'danger';
-  if (percentage >= 85) return 'critical';
-  if (percentage >= 70) return 'warning';
+// Matches the backend defaults until the settings have loaded.
+export const DEFAULT_THRESHOLDS: WarningThresholds = { warning: 70, critical: 85, danger: 95 };
+
+export function getWarningLevel(percentage: number, thresholds: WarningThresholds): WarningLevel {
+  if (percentage >= thresholds.danger) return 'danger';
+  if (percentage >= thresholds.critical) return 'critical';
+  if (percentage >= thresholds.warning) return 'warning';
   return 'safe';
 }
 
@@ -124,23 +140,24 @@ export function calculateTimeToLimit(
 export function getAdvancedWarningMessage(
   percentage: number,
   timeToLimit: string | null,
-  burnRate: number | null
+  burnRate: number | null,
+  thresholds: WarningThresholds
 ): string | null {
-  if (percentage >= 95) {
+  if (percentage >= thresholds.danger) {
     if (timeToLimit && timeToLimit !== "Limit reached") {
       return `🚨 Critical: ${percentage.toFixed(1)}% used. Approx. ${timeToLimit} remaining at current rate.`;
     }
     return `🚨 Critical: ${percentage.toFixed(1)}% used. Limit almost reached!`;
   }
   
-  if (percentage >= 85) {
+  if (percentage >= thresholds.critical) {
     if (timeToLimit) {
       return `⚠️ High usage: ${percentage.toFixed(1)}% used. Approx. ${timeToLimit} remaining.`;
     }
     return `⚠️ High usage: ${percentage.toFixed(1)}% used. Monitor consumption closely.`;
   }
   
-  if (percentage >= 70) {
+  if (percentage >= thresholds.warning) {
     if (burnRate && burnRate > 0) {
       return `⚡ Moderate usage: ${percentage.toFixed(1)}% used. Current rate: ${burnRate.toFixed(1)}/min.`;
     }
@@ -150,8 +167,8 @@ export function getAdvancedWarningMessage(
   return null;
 }
 
-export function shouldShowUrgentWarning(percentage: number, timeToLimit: string | null): boolean {
-  if (percentage >= 95) return true;
+export function shouldShowUrgentWarning(percentage: number, timeToLimit: string | null, thresholds: WarningThresholds): boolean {
+  if (percentage >= thresholds.danger) return true;
   if (timeToLimit === "< 1 min" || timeToLimit === "Limit reached") return true;
   return false;
 }
@@ -214,9 +231,7 @@ export function analyzeUsagePattern(
   };
 }
 
-export function getAdaptiveThresholds(pattern: UsagePattern, _period: Period): { warning: number; critical: number; danger: number } {
-  const baseThresholds = { warning: 70, critical: 85, danger: 95 };
-  
+export function getAdaptiveThresholds(pattern: UsagePattern, _period: Period, baseThresholds: WarningThresholds): WarningThresholds {  
   if (pattern.averageDailyUsage === 0) {
     return baseThresholds;
   }
@@ -241,14 +256,16 @@ export function getAdaptiveThresholds(pattern: UsagePattern, _period: Period): {
     adjustment -= 5;
   }
 
+  // Shift the configured thresholds by at most 10 points either way.
+  const shift = (threshold: number) => Math.max(0, Math.min(100, threshold + Math.max(-10, Math.min(10, adjustment))));
   return {
-    warning: Math.max(50, Math.min(80, baseThresholds.warning + adjustment)),
-    critical: Math.max(70, Math.min(90, baseThresholds.critical + adjustment)),
-    danger: Math.max(85, Math.min(98, baseThresholds.danger + adjustment))
+    warning: shift(baseThresholds.warning),
+    critical: shift(baseThresholds.critical),
+    danger: shift(baseThresholds.danger)
   };
 }
 
-export function getAdaptiveWarningLevel(percentage: number, thresholds: { warning: number; critical: number; danger: number }): WarningLevel {
+export function getAdaptiveWarningLevel(percentage: number, thresholds: WarningThresholds): WarningLevel {
   if (percentage >= thresholds.danger) return 'danger';
   if (percentage >= thresholds.critical) return 'critical';
   if (percentage >= thresholds.warning) return 'warning';
@@ -259,16 +276,15 @@ export function getSmartWarningMessage(
   percentage: number,
   pattern: UsagePattern,
   timeToLimit: string | null,
+  baseThresholds: WarningThresholds,
   isAdaptive: boolean = true
 ): string | null {
-  if (percentage < 50) return null;
-  
-  const thresholds = getAdaptiveThresholds(pattern, 'day');
+  const thresholds = getAdaptiveThresholds(pattern, 'day', baseThresholds);
   const level = getAdaptiveWarningLevel(percentage, thresholds);
   
   if (level === 'safe') return null;
   
-  const baseMessage = getAdvancedWarningMessage(percentage, timeToLimit, pattern.typicalBurnRate);
+  const baseMessage = getAdvancedWarningMessage(percentage, timeToLimit, pattern.typicalBurnRate, thresholds);
   
   if (!isAdaptive || !baseMessage) return baseMessage;
   
@@ -279,7 +295,7 @@ export function getSmartWarningMessage(
     insight = " You're usually more consistent - consider reviewing today's usage.";
   } else if (pattern.averageDailyUsage > 0 && percentage < pattern.averageDailyUsage / 1000000 * 100) {
     insight = " You're below your typical usage today.";
-  } else if (percentage > 85 && pattern.typicalBurnRate > 0) {
+  } else if (percentage > thresholds.critical && pattern.typicalBurnRate > 0) {
     const projectedUsage = pattern.typicalBurnRate * 8 * 60; // 8 hours projection
     if (projectedUsage > 800000) { // 800K projection
       insight = " Your current rate suggests heavy usage ahead.";